@@ -0,0 +1,169 @@
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use spotify_songs_graph_analysis::generators::{Generator, WeightSpec};
+use spotify_songs_graph_analysis::sample::uniform_subset;
+use spotify_songs_graph_analysis::{load_songs_from_csv, Graph, Song};
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::hint::black_box;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+const SEED: u64 = 210;
+const DATASET_SIZES: [usize; 3] = [500, 2000, usize::MAX];
+const SYNTHETIC_SIZES: [usize; 2] = [500, 2000];
+
+// Tracks live and peak heap usage so construction memory can be reported next to timings.
+struct CountingAllocator;
+
+static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            let allocated = ALLOCATED.fetch_add(layout.size(), Ordering::Relaxed) + layout.size();
+            PEAK.fetch_max(allocated, Ordering::Relaxed);
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        ALLOCATED.fetch_sub(layout.size(), Ordering::Relaxed);
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+fn report_peak_memory(label: &str, songs: &[Song]) {
+    let songs = songs.to_vec();
+    let baseline = ALLOCATED.load(Ordering::Relaxed);
+    PEAK.store(baseline, Ordering::Relaxed);
+    let graph = Graph::build_from_songs(songs);
+    let peak = PEAK.load(Ordering::Relaxed) - baseline;
+    let retained = ALLOCATED.load(Ordering::Relaxed) - baseline;
+    drop(graph);
+    println!("{}: peak {:.1} MiB during construction, {:.1} MiB retained", label, peak as f64 / 1048576.0, retained as f64 / 1048576.0);
+}
+
+fn dataset_subsets() -> Vec<(String, Vec<Song>)> {
+    let songs = load_songs_from_csv("Spotify_final_dataset.csv").expect("Failed to load songs");
+    DATASET_SIZES
+        .iter()
+        .map(|&size| {
+            let subset = uniform_subset(&songs, size, SEED);
+            (subset.len().to_string(), subset)
+        })
+        .collect()
+}
+
+fn synthetic_songs(count: usize, seed: u64) -> Vec<Song> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    (0..count)
+        .map(|i| Song {
+            artist_name: format!("artist {}", i % 100),
+            song_name: format!("song {}", i),
+            days: rng.gen_range(1..2500),
+            peak_position: rng.gen_range(1..200),
+            total_streams: rng.gen_range(1_000_000..900_000_000),
+        })
+        .collect()
+}
+
+fn inputs() -> Vec<(String, Vec<Song>)> {
+    let mut inputs: Vec<(String, Vec<Song>)> =
+        dataset_subsets().into_iter().map(|(size, songs)| (format!("dataset/{}", size), songs)).collect();
+    for size in SYNTHETIC_SIZES {
+        inputs.push((format!("synthetic/{}", size), synthetic_songs(size, SEED)));
+    }
+    inputs
+}
+
+fn bench_construction(c: &mut Criterion) {
+    let mut group = c.benchmark_group("build_from_songs");
+    group.sample_size(10);
+    for (label, songs) in inputs() {
+        report_peak_memory(&label, &songs);
+        group.bench_with_input(BenchmarkId::from_parameter(&label), &songs, |b, songs| {
+            b.iter_batched(|| songs.clone(), Graph::build_from_songs, BatchSize::LargeInput);
+        });
+    }
+    group.finish();
+}
+
+fn bench_knn(c: &mut Criterion) {
+    let mut group = c.benchmark_group("build_knn");
+    group.sample_size(10);
+    for (label, songs) in inputs() {
+        group.bench_with_input(BenchmarkId::from_parameter(&label), &songs, |b, songs| {
+            b.iter_batched(|| songs.clone(), |songs| Graph::build_knn(songs, 10), BatchSize::LargeInput);
+        });
+    }
+    group.finish();
+}
+
+fn bench_dijkstra(c: &mut Criterion) {
+    let mut group = c.benchmark_group("dijkstra_per_source");
+    group.sample_size(10);
+    for (label, songs) in inputs() {
+        let graph = Graph::build_from_songs(songs);
+        group.bench_with_input(BenchmarkId::from_parameter(&label), &graph, |b, graph| {
+            let mut source = 0;
+            b.iter(|| {
+                source = (source + 1) % graph.vertices.len();
+                black_box(graph.dijkstra(source))
+            });
+        });
+    }
+    group.finish();
+}
+
+fn bench_generated(c: &mut Criterion) {
+    let mut generator = Generator::new(SEED, WeightSpec::Uniform(1, 1000));
+    let graphs = [
+        ("erdos_renyi/2000", generator.erdos_renyi(2000, 0.01)),
+        ("barabasi_albert/2000", generator.barabasi_albert(2000, 3)),
+        ("watts_strogatz/2000", generator.watts_strogatz(2000, 10, 0.1)),
+        ("grid/50x40", generator.grid(50, 40)),
+    ];
+
+    let mut group = c.benchmark_group("dijkstra_generated");
+    for (label, graph) in &graphs {
+        group.bench_with_input(BenchmarkId::from_parameter(label), graph, |b, graph| {
+            let mut source = 0;
+            b.iter(|| {
+                source = (source + 1) % graph.vertices.len();
+                black_box(graph.dijkstra(source))
+            });
+        });
+    }
+    group.finish();
+
+    let mut group = c.benchmark_group("closeness_generated");
+    group.sample_size(10);
+    for (label, graph) in &graphs {
+        group.bench_with_input(BenchmarkId::from_parameter(label), graph, |b, graph| {
+            b.iter(|| black_box(graph.closeness_centrality()));
+        });
+    }
+    group.finish();
+}
+
+// Covers the 500 and 2k inputs. Closeness over the full dataset is one Dijkstra run per song
+// on a ~61M edge graph, hours per sample; it is `dijkstra_per_source` at full size times the
+// song count, so that group tracks it instead.
+fn bench_closeness(c: &mut Criterion) {
+    let mut group = c.benchmark_group("closeness_centrality");
+    group.sample_size(10);
+    for (label, songs) in inputs().into_iter().filter(|(_, songs)| songs.len() <= 2000) {
+        let graph = Graph::build_from_songs(songs);
+        group.bench_with_input(BenchmarkId::from_parameter(&label), &graph, |b, graph| {
+            b.iter(|| black_box(graph.closeness_centrality()));
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_construction, bench_knn, bench_dijkstra, bench_closeness, bench_generated);
+criterion_main!(benches);
@@ -0,0 +1,101 @@
+use super::centrality::Centrality;
+use super::graph::{Graph, Weight};
+use super::progress::{Progress, Silent};
+use super::song::Song;
+use std::collections::HashMap;
+
+/// How the song pair weights between two artists are combined into one edge weight.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WeightAggregation {
+    Mean,
+    Max,
+    Sum,
+}
+
+impl Graph {
+    /// One vertex per artist. The vertex's song_name is the artist's most streamed song,
+    /// days and total_streams are summed and peak_position is the best peak over their songs.
+    /// Edges between two songs by the same artist are dropped.
+    pub fn build_artist_graph(&self, aggregation: WeightAggregation) -> Graph {
+        let mut artist_index: HashMap<&str, usize> = HashMap::new();
+        let mut artist_of: Vec<usize> = Vec::with_capacity(self.vertices.len());
+        let mut artists: Vec<Song> = vec![];
+        let mut top_streams: Vec<u64> = vec![];
+
+        for song in &self.vertices {
+            let name = song.artist_name.trim();
+            let index = *artist_index.entry(name).or_insert_with(|| {
+                artists.push(Song {
+                    artist_name: name.to_string(),
+                    song_name: song.song_name.clone(),
+                    days: 0,
+                    peak_position: song.peak_position,
+                    total_streams: 0,
+                });
+                top_streams.push(song.total_streams);
+                artists.len() - 1
+            });
+
+            let artist = &mut artists[index];
+            artist.days += song.days;
+            artist.peak_position = artist.peak_position.min(song.peak_position);
+            artist.total_streams += song.total_streams;
+            if song.total_streams > top_streams[index] {
+                top_streams[index] = song.total_streams;
+                artist.song_name = song.song_name.clone();
+            }
+            artist_of.push(index);
+        }
+
+        let mut edges: HashMap<(usize, usize), (i64, Weight, usize)> = HashMap::new();
+        for (u, adj_list) in self.adjacency_list.iter().enumerate() {
+            for &(v, weight) in adj_list {
+                let (a, b) = (artist_of[u], artist_of[v]);
+                // every undirected edge is stored twice, so only count it from its smaller end
+                if a == b || u > v {
+                    continue;
+                }
+                let key = if a < b { (a, b) } else { (b, a) };
+                let entry = edges.entry(key).or_insert((0, Weight::MIN, 0));
+                entry.0 += weight as i64;
+                entry.1 = entry.1.max(weight);
+                entry.2 += 1;
+            }
+        }
+
+        let mut graph = Graph::new();
+        for artist in artists {
+            graph.add_vertex(artist);
+        }
+
+        let mut edges: Vec<_> = edges.into_iter().collect();
+        edges.sort_unstable_by_key(|&(key, _)| key);
+        for ((a, b), (sum, max, count)) in edges {
+            let weight = match aggregation {
+                WeightAggregation::Mean => (sum as f64 / count as f64).round() as Weight,
+                WeightAggregation::Max => max,
+                WeightAggregation::Sum => sum as Weight,
+            };
+            graph.add_edge(a, b, weight);
+        }
+
+        graph
+    }
+
+    /// Prints the six most central vertices of an artist graph.
+    pub fn print_most_central_artists(&self) {
+        self.print_most_central_artists_observed(&Silent);
+    }
+
+    /// `print_most_central_artists` reporting progress, noting when a cancelled run left the
+    /// ranking partial.
+    pub fn print_most_central_artists_observed(&self, progress: &dyn Progress) {
+        let ranking = self.ranking_observed(Centrality::Closeness, progress);
+        if ranking.len() < self.vertices.len() {
+            println!("Cancelled: only {} of {} artists were scored", ranking.len(), self.vertices.len());
+        }
+        for entry in ranking.iter().take(6) {
+            println!("Depth {}: Artist: {}, Top song: {}, Closeness: {:.2}", entry.competition_rank, entry.artist_name, entry.song_name, entry.score);
+        }
+    }
+}
@@ -0,0 +1,139 @@
+use super::graph::{closeness, CentralityScores, Graph, Vertex};
+use super::progress::{Progress, Silent};
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+
+/// The centrality measures available on a `Graph`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Centrality {
+    Closeness,
+    Harmonic,
+    WassermanFaust,
+}
+
+impl Centrality {
+    /// Every measure, in a fixed order.
+    pub const ALL: [Centrality; 3] = [Centrality::Closeness, Centrality::Harmonic, Centrality::WassermanFaust];
+
+    /// Name used on the command line and in reports.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Centrality::Closeness => "closeness",
+            Centrality::Harmonic => "harmonic",
+            Centrality::WassermanFaust => "wasserman-faust",
+        }
+    }
+}
+
+impl fmt::Display for Centrality {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Centrality {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        Centrality::ALL
+            .into_iter()
+            .find(|metric| metric.name() == text)
+            .ok_or_else(|| format!("unknown centrality `{}`, expected closeness, harmonic or wasserman-faust", text))
+    }
+}
+
+impl Graph {
+    /// Harmonic centrality, the sum of `1 / d(u, v)` over every other vertex `v`, divided by
+    /// `n - 1`. Unreachable vertices add nothing, so disconnected graphs need no special
+    /// case, and the division keeps scores comparable between graphs of different sizes.
+    /// Pairs at distance zero are skipped rather than counted as infinitely close.
+    pub fn harmonic_centrality(&self) -> CentralityScores {
+        self.centrality(Centrality::Harmonic)
+    }
+
+    /// Wasserman and Faust closeness: `(r - 1) / (n - 1)` times `(r - 1) / sum d(u, v)`, where
+    /// `r` counts the vertices `u` reaches including itself and the sum runs over them. The
+    /// first factor discounts vertices in small components, so an isolated song scores 0
+    /// instead of looking maximally central. On a connected graph it is `(n - 1) / sum d(u, v)`,
+    /// which is `closeness_centrality` times `(n - 1) / (1000 n)`, so both rank songs alike.
+    pub fn wasserman_faust_closeness(&self) -> CentralityScores {
+        self.centrality(Centrality::WassermanFaust)
+    }
+
+    /// Scores every vertex with the chosen measure.
+    pub fn centrality(&self, metric: Centrality) -> CentralityScores {
+        self.centrality_observed(metric, &Silent)
+    }
+
+    /// `centrality` reporting one step per source vertex to `progress`. If `progress` cancels
+    /// the run, only the vertices scored so far are in the result; their scores are final.
+    pub fn centrality_observed(&self, metric: Centrality, progress: &dyn Progress) -> CentralityScores {
+        let mut scores = HashMap::new();
+        progress.start(metric.name(), self.vertices.len());
+        self.score_sources(metric, 0..self.vertices.len(), &mut scores, progress);
+        progress.finish();
+        scores
+    }
+
+    /// Prints the six most central songs under `metric`.
+    pub fn print_most_central(&self, metric: Centrality) {
+        self.print_most_central_observed(metric, &Silent);
+    }
+
+    /// `print_most_central` reporting progress, noting when a cancelled run left the ranking partial.
+    pub fn print_most_central_observed(&self, metric: Centrality, progress: &dyn Progress) {
+        let ranking = self.ranking_observed(metric, progress);
+        if ranking.len() < self.vertices.len() {
+            println!("Cancelled: only {} of {} songs were scored", ranking.len(), self.vertices.len());
+        }
+        for entry in ranking.iter().take(6) {
+            println!("Depth {}: Song: {}, Artist: {}, {}: {:.4}", entry.competition_rank, entry.song_name, entry.artist_name, metric, entry.score);
+        }
+    }
+
+    // Runs dijkstra from each of `sources` and adds its score to `scores`, reporting the
+    // number of scored vertices to `progress` and stopping early when it cancels.
+    pub(crate) fn score_sources(&self, metric: Centrality, sources: impl IntoIterator<Item = Vertex>, scores: &mut CentralityScores, progress: &dyn Progress) {
+        let n = self.vertices.len();
+        for source in sources {
+            if progress.is_cancelled() {
+                break;
+            }
+            let distances = self.dijkstra(source);
+            let score = match metric {
+                Centrality::Closeness => closeness(&distances),
+                Centrality::Harmonic => harmonic(n, source, &distances),
+                Centrality::WassermanFaust => wasserman_faust(n, &distances),
+            };
+            let song = &self.vertices[source];
+            scores.insert(source, (song.song_name.clone(), song.artist_name.clone(), score));
+            progress.advance(scores.len());
+        }
+    }
+}
+
+// Harmonic centrality of `source` in a graph of `n` vertices.
+fn harmonic(n: usize, source: Vertex, distances: &[f32]) -> f32 {
+    let others = n.saturating_sub(1).max(1) as f32;
+    let sum: f32 = distances
+        .iter()
+        .enumerate()
+        .filter(|&(v, &d)| v != source && d > 0.0 && d < f32::MAX)
+        .map(|(_, &d)| 1.0 / d)
+        .sum();
+    sum / others
+}
+
+// Wasserman-Faust closeness of a vertex in a graph of `n` vertices.
+fn wasserman_faust(n: usize, distances: &[f32]) -> f32 {
+    let others = n.saturating_sub(1) as f32;
+    let reached: Vec<f32> = distances.iter().copied().filter(|&d| d < f32::MAX).collect();
+    let r = reached.len() as f32;
+    let sum: f32 = reached.iter().sum();
+    if r <= 1.0 || sum <= 0.0 {
+        0.0
+    } else {
+        (r - 1.0) / others * (r - 1.0) / sum
+    }
+}
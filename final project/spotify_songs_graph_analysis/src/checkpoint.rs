@@ -0,0 +1,174 @@
+use super::centrality::Centrality;
+use super::graph::{CentralityScores, Graph, Vertex};
+use super::progress::Progress;
+use std::collections::{BTreeMap, HashMap};
+use std::error::Error;
+use std::fs;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+const HEADER: &str = "# song graph centrality checkpoint v1";
+
+const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+struct Fnv(u64);
+
+impl Fnv {
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 = (self.0 ^ byte as u64).wrapping_mul(FNV_PRIME);
+        }
+    }
+
+    fn write_u64(&mut self, value: u64) {
+        self.write(&value.to_le_bytes());
+    }
+
+    fn write_str(&mut self, text: &str) {
+        self.write_u64(text.len() as u64);
+        self.write(text.as_bytes());
+    }
+}
+
+/// Scores of the sources completed so far in an all-sources centrality run, tied to the
+/// graph they were computed on by its fingerprint.
+///
+/// On disk it is a text file: a header, `fingerprint`, `metric` and `vertices` lines, then
+/// one `vertex score` line per completed source.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Checkpoint {
+    pub fingerprint: u64,
+    pub metric: Centrality,
+    pub vertex_count: usize,
+    pub scores: BTreeMap<Vertex, f32>,
+}
+
+impl Checkpoint {
+    /// Reads a checkpoint written by `write`. Errors name the file and line that could not be
+    /// parsed, including score lines for vertices outside `vertices`.
+    pub fn read(path: impl AsRef<Path>) -> Result<Self, Box<dyn Error>> {
+        let path = path.as_ref();
+        let text = fs::read_to_string(path)?;
+        let mut lines = text.lines().enumerate();
+        let invalid = |number: usize, message: &str| format!("{}:{}: {}", path.display(), number + 1, message);
+
+        if lines.next().map(|(_, line)| line) != Some(HEADER) {
+            return Err(invalid(0, "not a centrality checkpoint").into());
+        }
+        let mut field = |name: &str| -> Result<String, String> {
+            let (number, line) = lines.next().ok_or_else(|| invalid(0, &format!("missing `{}`", name)))?;
+            line.strip_prefix(name)
+                .and_then(|rest| rest.strip_prefix(' '))
+                .map(str::to_string)
+                .ok_or_else(|| invalid(number, &format!("expected `{}`", name)))
+        };
+        let fingerprint = u64::from_str_radix(&field("fingerprint")?, 16)?;
+        let metric: Centrality = field("metric")?.parse()?;
+        let vertex_count: usize = field("vertices")?.parse()?;
+
+        let mut scores = BTreeMap::new();
+        for (number, line) in lines {
+            let parsed = line.split_once(' ').and_then(|(vertex, score)| Some((vertex.parse::<Vertex>().ok()?, score.parse::<f32>().ok()?)));
+            match parsed {
+                Some((vertex, score)) if vertex < vertex_count => scores.insert(vertex, score),
+                _ => return Err(invalid(number, &format!("invalid score line `{}`", line)).into()),
+            };
+        }
+        Ok(Checkpoint { fingerprint, metric, vertex_count, scores })
+    }
+
+    /// Writes the checkpoint to a temporary file next to `path` and renames it over `path`,
+    /// so a crash while writing leaves the previous checkpoint intact.
+    pub fn write(&self, path: impl AsRef<Path>) -> Result<(), Box<dyn Error>> {
+        let path = path.as_ref();
+        let mut temporary = path.as_os_str().to_owned();
+        temporary.push(".tmp");
+
+        let mut writer = BufWriter::new(fs::File::create(&temporary)?);
+        writeln!(writer, "{}", HEADER)?;
+        writeln!(writer, "fingerprint {:016x}", self.fingerprint)?;
+        writeln!(writer, "metric {}", self.metric)?;
+        writeln!(writer, "vertices {}", self.vertex_count)?;
+        for (vertex, score) in &self.scores {
+            // `Display` prints the shortest text that parses back to the same f32, including inf and NaN
+            writeln!(writer, "{} {}", vertex, score)?;
+        }
+        writer.into_inner().map_err(|e| e.into_error())?.sync_all()?;
+        fs::rename(&temporary, path)?;
+        Ok(())
+    }
+}
+
+impl Graph {
+    /// 64-bit FNV-1a hash of every song and every adjacency list, in order. Equal graphs built
+    /// from the same data have the same fingerprint on every run and platform; changing a
+    /// song, an edge or the vertex order changes it.
+    pub fn fingerprint(&self) -> u64 {
+        let mut hash = Fnv(FNV_OFFSET);
+        hash.write_u64(self.vertices.len() as u64);
+        for song in &self.vertices {
+            hash.write_str(&song.artist_name);
+            hash.write_str(&song.song_name);
+            hash.write_u64(song.days as u64);
+            hash.write_u64(song.peak_position as u64);
+            hash.write_u64(song.total_streams);
+        }
+        for adj_list in &self.adjacency_list {
+            hash.write_u64(adj_list.len() as u64);
+            for &(v, weight) in adj_list {
+                hash.write_u64(v as u64);
+                hash.write_u64(weight as u64);
+            }
+        }
+        hash.0
+    }
+
+    /// `centrality_observed` that saves the completed sources to `path` after every `interval`
+    /// of them and when cancelled. If `path` already holds a checkpoint of this graph and
+    /// metric, the sources in it are not recomputed, so a run that died resumes where its last
+    /// checkpoint left off. A checkpoint of another graph or metric is an error rather than
+    /// being overwritten. The finished checkpoint is kept, so running again is instant.
+    pub fn centrality_checkpointed(&self, metric: Centrality, path: impl AsRef<Path>, interval: usize, progress: &dyn Progress) -> Result<CentralityScores, Box<dyn Error>> {
+        let path = path.as_ref();
+        let fingerprint = self.fingerprint();
+        let mut checkpoint = if path.exists() {
+            let checkpoint = Checkpoint::read(path)?;
+            if checkpoint.fingerprint != fingerprint || checkpoint.vertex_count != self.vertices.len() {
+                return Err(format!(
+                    "checkpoint {} was written for a different graph (fingerprint {:016x}, this graph {:016x})",
+                    path.display(),
+                    checkpoint.fingerprint,
+                    fingerprint
+                )
+                .into());
+            }
+            if checkpoint.metric != metric {
+                return Err(format!("checkpoint {} holds {} scores, not {}", path.display(), checkpoint.metric, metric).into());
+            }
+            checkpoint
+        } else {
+            Checkpoint { fingerprint, metric, vertex_count: self.vertices.len(), scores: BTreeMap::new() }
+        };
+
+        let mut scores: CentralityScores = HashMap::new();
+        for (&vertex, &score) in &checkpoint.scores {
+            let song = &self.vertices[vertex];
+            scores.insert(vertex, (song.song_name.clone(), song.artist_name.clone(), score));
+        }
+        let remaining: Vec<Vertex> = (0..self.vertices.len()).filter(|vertex| !scores.contains_key(vertex)).collect();
+
+        progress.start(metric.name(), self.vertices.len());
+        let mut written = Ok(());
+        for sources in remaining.chunks(interval.max(1)) {
+            self.score_sources(metric, sources.iter().copied(), &mut scores, progress);
+            checkpoint.scores = scores.iter().map(|(&vertex, &(_, _, score))| (vertex, score)).collect();
+            written = checkpoint.write(path);
+            if written.is_err() || progress.is_cancelled() {
+                break;
+            }
+        }
+        progress.finish();
+        written.map(|_| scores)
+    }
+}
@@ -0,0 +1,202 @@
+use super::graph::{Graph, Vertex, Weight};
+use super::progress::{Progress, Silent};
+
+/// Totals from one pass over every triangle, from which all the clustering measures follow.
+struct Triangles {
+    count: usize,
+    per_vertex: Vec<usize>,
+    // sum of each vertex's triangle intensities, the geometric mean of the three edge weights
+    // scaled by the largest weight in the graph
+    intensity: Vec<f64>,
+    // sum of the three edge weights of every triangle
+    closed_weight: f64,
+}
+
+/// Clustering measures of `GraphStats`, computed from a single triangle pass.
+pub(crate) struct Clustering {
+    pub triangle_count: usize,
+    pub average: f64,
+    pub weighted_average: f64,
+    pub transitivity: f64,
+    pub weighted_transitivity: f64,
+}
+
+impl Graph {
+    /// Number of triangles each vertex belongs to.
+    pub fn triangles_per_vertex(&self) -> Vec<usize> {
+        self.triangles(&Silent).per_vertex
+    }
+
+    /// Number of distinct triangles in the graph.
+    pub fn triangle_count(&self) -> usize {
+        self.triangles(&Silent).count
+    }
+
+    /// Fraction of each vertex's neighbour pairs that are themselves connected.
+    pub fn local_clustering(&self) -> Vec<f64> {
+        let triangles = self.triangles_per_vertex().into_iter().map(|triangles| triangles as f64);
+        local(triangles, self.simple_degrees())
+    }
+
+    /// Weighted local clustering after Onnela et al.: each triangle counts as the geometric
+    /// mean of its three edge weights, scaled by the largest weight in the graph.
+    pub fn weighted_local_clustering(&self) -> Vec<f64> {
+        local(self.triangles(&Silent).intensity, self.simple_degrees())
+    }
+
+    /// Mean of `local_clustering` over all vertices.
+    pub fn average_clustering(&self) -> f64 {
+        mean(&self.local_clustering())
+    }
+
+    /// Mean of `weighted_local_clustering` over all vertices.
+    pub fn weighted_average_clustering(&self) -> f64 {
+        mean(&self.weighted_local_clustering())
+    }
+
+    /// Transitivity, the share of connected triples that are closed into a triangle.
+    pub fn global_clustering(&self) -> f64 {
+        transitivity(self.triangle_count(), &self.simple_degrees())
+    }
+
+    /// Weighted transitivity after Opsahl and Panzarasa, valuing each triple by the arithmetic
+    /// mean of its two edge weights.
+    pub fn weighted_global_clustering(&self) -> f64 {
+        weighted_transitivity(self.triangles(&Silent).closed_weight, &self.simple_adjacency())
+    }
+
+    /// Every clustering measure from one pass over the triangles, reporting one step per
+    /// vertex to `progress`. If `progress` cancels the pass, the measures only count the
+    /// triangles found so far.
+    pub(crate) fn clustering_observed(&self, progress: &dyn Progress) -> Clustering {
+        let adjacency = self.simple_adjacency();
+        let degrees: Vec<usize> = adjacency.iter().map(Vec::len).collect();
+        let triangles = self.triangles(progress);
+        Clustering {
+            triangle_count: triangles.count,
+            average: mean(&local(triangles.per_vertex.iter().map(|&triangles| triangles as f64), degrees.iter().copied())),
+            weighted_average: mean(&local(triangles.intensity, degrees.iter().copied())),
+            transitivity: transitivity(triangles.count, &degrees),
+            weighted_transitivity: weighted_transitivity(triangles.closed_weight, &adjacency),
+        }
+    }
+
+    fn triangles(&self, progress: &dyn Progress) -> Triangles {
+        let max_weight = self.adjacency_list.iter().flatten().map(|&(_, weight)| weight).max().unwrap_or(0);
+        let scale = max_weight as f64;
+        let mut triangles = Triangles {
+            count: 0,
+            per_vertex: vec![0; self.vertices.len()],
+            intensity: vec![0.0; self.vertices.len()],
+            closed_weight: 0.0,
+        };
+        self.for_each_triangle(progress, |u, v, w, (uv, vw, uw)| {
+            triangles.count += 1;
+            triangles.closed_weight += (uv + vw + uw) as f64;
+            let intensity = if max_weight > 0 { (uv as f64 / scale * vw as f64 / scale * uw as f64 / scale).cbrt() } else { 0.0 };
+            for vertex in [u, v, w] {
+                triangles.per_vertex[vertex] += 1;
+                triangles.intensity[vertex] += intensity;
+            }
+        });
+        triangles
+    }
+
+    // Adjacency lists without self loops or repeated neighbours, sorted by neighbour.
+    fn simple_adjacency(&self) -> Vec<Vec<(Vertex, Weight)>> {
+        self.adjacency_list
+            .iter()
+            .enumerate()
+            .map(|(u, adj_list)| {
+                let mut neighbours: Vec<(Vertex, Weight)> = adj_list.iter().copied().filter(|&(v, _)| v != u).collect();
+                neighbours.sort_by_key(|&(v, _)| v);
+                neighbours.dedup_by_key(|&mut (v, _)| v);
+                neighbours
+            })
+            .collect()
+    }
+
+    fn simple_degrees(&self) -> Vec<usize> {
+        self.simple_adjacency().iter().map(|adj_list| adj_list.len()).collect()
+    }
+
+    // Visits every triangle once in O(m^1.5): edges are oriented from lower to higher
+    // (degree, index) rank, so each vertex only scans its few higher ranked neighbours.
+    fn for_each_triangle(&self, progress: &dyn Progress, mut visit: impl FnMut(Vertex, Vertex, Vertex, (Weight, Weight, Weight))) {
+        let adjacency = self.simple_adjacency();
+        let rank = |v: Vertex| (adjacency[v].len(), v);
+        let forward: Vec<Vec<(Vertex, Weight)>> = adjacency
+            .iter()
+            .enumerate()
+            .map(|(u, adj_list)| adj_list.iter().copied().filter(|&(v, _)| rank(v) > rank(u)).collect())
+            .collect();
+
+        let mut marked: Vec<Option<Weight>> = vec![None; self.vertices.len()];
+        progress.start("counting triangles", forward.len());
+        for (u, out) in forward.iter().enumerate() {
+            if progress.is_cancelled() {
+                break;
+            }
+            for &(v, weight) in out {
+                marked[v] = Some(weight);
+            }
+            for &(v, uv) in out {
+                for &(w, vw) in &forward[v] {
+                    if let Some(uw) = marked[w] {
+                        visit(u, v, w, (uv, vw, uw));
+                    }
+                }
+            }
+            for &(v, _) in out {
+                marked[v] = None;
+            }
+            progress.advance(u + 1);
+        }
+        progress.finish();
+    }
+}
+
+// Local clustering from each vertex's triangle total, which is 0 below degree 2.
+fn local(triangles: impl IntoIterator<Item = f64>, degrees: impl IntoIterator<Item = usize>) -> Vec<f64> {
+    triangles
+        .into_iter()
+        .zip(degrees)
+        .map(|(triangles, degree)| {
+            if degree < 2 {
+                0.0
+            } else {
+                2.0 * triangles / (degree * (degree - 1)) as f64
+            }
+        })
+        .collect()
+}
+
+fn transitivity(triangle_count: usize, degrees: &[usize]) -> f64 {
+    let triples: usize = degrees.iter().map(|&degree| degree * degree.saturating_sub(1) / 2).sum();
+    if triples == 0 {
+        return 0.0;
+    }
+    3.0 * triangle_count as f64 / triples as f64
+}
+
+fn weighted_transitivity(closed_weight: f64, adjacency: &[Vec<(Vertex, Weight)>]) -> f64 {
+    let total: f64 = adjacency
+        .iter()
+        .map(|adj_list| {
+            let strength: Weight = adj_list.iter().map(|&(_, weight)| weight).sum();
+            adj_list.len().saturating_sub(1) as f64 * strength as f64 / 2.0
+        })
+        .sum();
+    if total == 0.0 {
+        return 0.0;
+    }
+    closed_weight / total
+}
+
+fn mean(values: &[f64]) -> f64 {
+    if values.is_empty() {
+        0.0
+    } else {
+        values.iter().sum::<f64>() / values.len() as f64
+    }
+}
@@ -0,0 +1,51 @@
+use super::graph::{Graph, Weight};
+use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
+use std::collections::BTreeMap;
+
+const MAX_ROUNDS: usize = 100;
+
+impl Graph {
+    /// Communities found by weighted label propagation. Every vertex starts in its own
+    /// community and, visiting vertices in a seeded random order, repeatedly adopts the label
+    /// with the largest total edge weight among its neighbours, the smallest label on ties.
+    /// Stops when no label changes or after 100 rounds. Labels are numbered from 0 in order
+    /// of first vertex, like `connected_components`.
+    pub fn label_propagation(&self, seed: u64) -> Vec<usize> {
+        let mut labels: Vec<usize> = (0..self.vertices.len()).collect();
+        let mut order: Vec<usize> = (0..self.vertices.len()).collect();
+        let mut rng = StdRng::seed_from_u64(seed);
+
+        for _ in 0..MAX_ROUNDS {
+            order.shuffle(&mut rng);
+            let mut changed = false;
+            for &u in &order {
+                let mut totals: BTreeMap<usize, Weight> = BTreeMap::new();
+                for &(v, weight) in &self.adjacency_list[u] {
+                    *totals.entry(labels[v]).or_insert(0) += weight;
+                }
+                let best = totals.into_iter().fold(None, |best: Option<(usize, Weight)>, (label, total)| match best {
+                    Some((_, best_total)) if best_total >= total => best,
+                    _ => Some((label, total)),
+                });
+                if let Some((label, _)) = best {
+                    if label != labels[u] {
+                        labels[u] = label;
+                        changed = true;
+                    }
+                }
+            }
+            if !changed {
+                break;
+            }
+        }
+
+        let mut renumbered: BTreeMap<usize, usize> = BTreeMap::new();
+        labels
+            .into_iter()
+            .map(|label| {
+                let next = renumbered.len();
+                *renumbered.entry(label).or_insert(next)
+            })
+            .collect()
+    }
+}
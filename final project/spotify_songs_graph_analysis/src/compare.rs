@@ -0,0 +1,237 @@
+use super::centrality::Centrality;
+use super::graph::{Graph, Vertex};
+use super::progress::{Progress, Silent};
+use super::ranking::compare_scores;
+use csv::Writer;
+use std::cmp::Ordering;
+use std::error::Error;
+use std::fmt;
+
+/// Column name used for the streaming ranking next to the centrality measures.
+pub const STREAMS: &str = "total_streams";
+
+/// One song's scores and ranks under every compared measure. Rank 1 is the most central or
+/// most streamed; tied songs share the average of the ranks they span.
+#[derive(Debug, Clone)]
+pub struct ComparisonRow {
+    pub vertex: Vertex,
+    pub song_name: String,
+    pub artist_name: String,
+    pub total_streams: u64,
+    pub stream_rank: f64,
+    /// Indexed like `CentralityComparison::metrics`.
+    pub scores: Vec<f32>,
+    /// Indexed like `CentralityComparison::metrics`.
+    pub ranks: Vec<f64>,
+}
+
+/// Rank agreement between two rankings, each named after a measure or `total_streams`.
+#[derive(Debug, Clone)]
+pub struct RankCorrelation {
+    pub first: String,
+    pub second: String,
+    pub spearman: f64,
+    pub kendall: f64,
+}
+
+/// Several centrality measures computed on one graph, with their rank correlations to each
+/// other and to total streams.
+#[derive(Debug, Clone)]
+pub struct CentralityComparison {
+    pub metrics: Vec<Centrality>,
+    /// One row per vertex, in vertex order.
+    pub rows: Vec<ComparisonRow>,
+    pub correlations: Vec<RankCorrelation>,
+}
+
+impl Graph {
+    /// Runs every measure in `metrics` and compares their rankings.
+    pub fn compare_centrality(&self, metrics: &[Centrality]) -> CentralityComparison {
+        self.compare_centrality_observed(metrics, &Silent)
+    }
+
+    /// `compare_centrality` reporting each measure's run to `progress`. If `progress` cancels,
+    /// the vertices left unscored have NaN scores and the comparison is partial.
+    pub fn compare_centrality_observed(&self, metrics: &[Centrality], progress: &dyn Progress) -> CentralityComparison {
+        let streams: Vec<u64> = self.vertices.iter().map(|song| song.total_streams).collect();
+        let stream_ranks = average_ranks(&streams, |a, b| b.cmp(a));
+
+        let mut scores: Vec<Vec<f32>> = vec![];
+        for &metric in metrics {
+            let by_vertex = self.centrality_observed(metric, progress);
+            scores.push((0..self.vertices.len()).map(|v| by_vertex.get(&v).map_or(f32::NAN, |entry| entry.2)).collect());
+        }
+        let ranks: Vec<Vec<f64>> = scores.iter().map(|scores| fractional_ranks(scores)).collect();
+
+        let rows = self
+            .vertices
+            .iter()
+            .enumerate()
+            .map(|(v, song)| ComparisonRow {
+                vertex: v,
+                song_name: song.song_name.clone(),
+                artist_name: song.artist_name.clone(),
+                total_streams: song.total_streams,
+                stream_rank: stream_ranks[v],
+                scores: scores.iter().map(|scores| scores[v]).collect(),
+                ranks: ranks.iter().map(|ranks| ranks[v]).collect(),
+            })
+            .collect();
+
+        let mut named: Vec<(String, &Vec<f64>)> = metrics.iter().map(|m| m.name().to_string()).zip(&ranks).collect();
+        named.push((STREAMS.to_string(), &stream_ranks));
+        let mut correlations = vec![];
+        for i in 0..named.len() {
+            for j in (i + 1)..named.len() {
+                correlations.push(RankCorrelation {
+                    first: named[i].0.clone(),
+                    second: named[j].0.clone(),
+                    spearman: spearman(named[i].1, named[j].1),
+                    kendall: kendall_tau_b(named[i].1, named[j].1),
+                });
+            }
+        }
+
+        CentralityComparison { metrics: metrics.to_vec(), rows, correlations }
+    }
+}
+
+impl CentralityComparison {
+    /// Correlation between two rankings by name, in either order.
+    pub fn correlation(&self, first: &str, second: &str) -> Option<&RankCorrelation> {
+        self.correlations
+            .iter()
+            .find(|c| (c.first == first && c.second == second) || (c.first == second && c.second == first))
+    }
+
+    /// The `n` songs whose rank under `metric` is furthest from their streaming rank, largest
+    /// gap first. Equal gaps keep vertex order.
+    pub fn divergent(&self, metric: Centrality, n: usize) -> Vec<&ComparisonRow> {
+        let Some(index) = self.metrics.iter().position(|&m| m == metric) else {
+            return vec![];
+        };
+        let gap = |row: &ComparisonRow| (row.ranks[index] - row.stream_rank).abs();
+        let mut rows: Vec<&ComparisonRow> = self.rows.iter().collect();
+        rows.sort_by(|a, b| gap(b).total_cmp(&gap(a)).then_with(|| a.vertex.cmp(&b.vertex)));
+        rows.truncate(n);
+        rows
+    }
+
+    /// Writes one row per song with its streams, streaming rank, and each measure's score and rank.
+    pub fn write_csv(&self, file_path: &str) -> Result<(), Box<dyn Error>> {
+        let mut writer = Writer::from_path(file_path)?;
+        let mut header = vec!["artist_name".to_string(), "song_name".to_string(), STREAMS.to_string(), "stream_rank".to_string()];
+        for metric in &self.metrics {
+            header.push(metric.name().to_string());
+            header.push(format!("{}_rank", metric));
+        }
+        writer.write_record(&header)?;
+
+        for row in &self.rows {
+            let mut record = vec![row.artist_name.clone(), row.song_name.clone(), row.total_streams.to_string(), row.stream_rank.to_string()];
+            for (score, rank) in row.scores.iter().zip(&row.ranks) {
+                record.push(format!("{:.6}", score));
+                record.push(rank.to_string());
+            }
+            writer.write_record(&record)?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+}
+
+/// Ranks scores from highest (rank 1) to lowest with NaN last, giving tied scores the average
+/// of the ranks they span.
+pub fn fractional_ranks(scores: &[f32]) -> Vec<f64> {
+    average_ranks(scores, |a, b| compare_scores(*a, *b))
+}
+
+fn average_ranks<T>(values: &[T], compare: impl Fn(&T, &T) -> Ordering) -> Vec<f64> {
+    let mut order: Vec<usize> = (0..values.len()).collect();
+    order.sort_by(|&a, &b| compare(&values[a], &values[b]));
+
+    let mut ranks = vec![0.0; values.len()];
+    let mut start = 0;
+    while start < order.len() {
+        let mut end = start + 1;
+        while end < order.len() && compare(&values[order[start]], &values[order[end]]) == Ordering::Equal {
+            end += 1;
+        }
+        // positions start..end hold ranks start + 1 to end
+        let rank = (start + 1 + end) as f64 / 2.0;
+        for &i in &order[start..end] {
+            ranks[i] = rank;
+        }
+        start = end;
+    }
+    ranks
+}
+
+/// Spearman's rho: the Pearson correlation of two rank vectors. 0 when either is constant.
+pub fn spearman(a: &[f64], b: &[f64]) -> f64 {
+    let n = a.len().min(b.len());
+    if n == 0 {
+        return 0.0;
+    }
+    let mean_a = a[..n].iter().sum::<f64>() / n as f64;
+    let mean_b = b[..n].iter().sum::<f64>() / n as f64;
+    let (mut cov, mut var_a, mut var_b) = (0.0, 0.0, 0.0);
+    for i in 0..n {
+        let (da, db) = (a[i] - mean_a, b[i] - mean_b);
+        cov += da * db;
+        var_a += da * da;
+        var_b += db * db;
+    }
+    if var_a == 0.0 || var_b == 0.0 {
+        0.0
+    } else {
+        cov / (var_a * var_b).sqrt()
+    }
+}
+
+/// Kendall's tau-b, which corrects for ties in either ranking. O(n^2) over all pairs; 0 when
+/// either ranking is constant.
+pub fn kendall_tau_b(a: &[f64], b: &[f64]) -> f64 {
+    let n = a.len().min(b.len());
+    let (mut concordant, mut discordant, mut tied_a, mut tied_b) = (0i64, 0i64, 0i64, 0i64);
+    for i in 0..n {
+        for j in (i + 1)..n {
+            let da = a[i].total_cmp(&a[j]);
+            let db = b[i].total_cmp(&b[j]);
+            match (da, db) {
+                (Ordering::Equal, Ordering::Equal) => {}
+                (Ordering::Equal, _) => tied_a += 1,
+                (_, Ordering::Equal) => tied_b += 1,
+                _ if da == db => concordant += 1,
+                _ => discordant += 1,
+            }
+        }
+    }
+    let denominator = (((concordant + discordant + tied_a) as f64) * ((concordant + discordant + tied_b) as f64)).sqrt();
+    if denominator == 0.0 {
+        0.0
+    } else {
+        (concordant - discordant) as f64 / denominator
+    }
+}
+
+impl fmt::Display for CentralityComparison {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "{:<36} {:>9} {:>9}", "Rank correlation", "Spearman", "Kendall")?;
+        for c in &self.correlations {
+            writeln!(f, "{:<36} {:>9.4} {:>9.4}", format!("{} / {}", c.first, c.second), c.spearman, c.kendall)?;
+        }
+
+        for (index, &metric) in self.metrics.iter().enumerate() {
+            writeln!(f, "Largest gaps between {} rank and streaming rank:", metric)?;
+            for row in self.divergent(metric, 10) {
+                writeln!(
+                    f,
+                    "  Song: {}, Artist: {}, {} rank: {}, Streaming rank: {}",
+                    row.song_name, row.artist_name, metric, row.ranks[index], row.stream_rank
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
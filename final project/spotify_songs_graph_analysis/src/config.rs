@@ -0,0 +1,482 @@
+use super::centrality::Centrality;
+use super::graph::{Graph, Weight};
+use super::layout::{Colouring, LayoutOptions};
+use super::progress::{Progress, Silent};
+use super::query::SongQuery;
+use super::sample::{stratified_subset, uniform_subset};
+use super::song::Song;
+use super::source::{format_for_path, source_for_format, Column, ColumnMapping, SongSource};
+use super::weights::WeightParams;
+use csv::Writer;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io::Write;
+use std::ops::Bound;
+use std::path::Path;
+
+/// A config file that could not be read, parsed or validated. `key` is the dotted path of
+/// the offending setting, e.g. `filters.min_days`, when the problem is with one setting.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigError {
+    pub key: Option<String>,
+    pub message: String,
+}
+
+impl ConfigError {
+    fn at(key: impl Into<String>, message: impl Into<String>) -> Self {
+        ConfigError { key: Some(key.into()), message: message.into() }
+    }
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.key {
+            Some(key) => write!(f, "{}: {}", key, self.message),
+            None => write!(f, "{}", self.message),
+        }
+    }
+}
+
+impl Error for ConfigError {}
+
+/// Where the songs come from. `format` defaults to the file extension and `columns` maps
+/// song fields to header names, or to positions written as `#3`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct InputConfig {
+    pub path: String,
+    pub format: Option<String>,
+    pub columns: BTreeMap<String, String>,
+}
+
+impl Default for InputConfig {
+    fn default() -> Self {
+        InputConfig { path: "Spotify_final_dataset.csv".to_string(), format: None, columns: BTreeMap::new() }
+    }
+}
+
+/// Which songs the graph is built over, mirroring the `SongQuery` options. Bounds are inclusive.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct FilterConfig {
+    pub min_days: Option<u32>,
+    pub max_days: Option<u32>,
+    pub min_peak: Option<u32>,
+    pub max_peak: Option<u32>,
+    pub min_streams: Option<u64>,
+    pub max_streams: Option<u64>,
+    pub include_artists: Vec<String>,
+    pub exclude_artists: Vec<String>,
+    pub artist_contains: Option<String>,
+    pub song_contains: Option<String>,
+    /// Keep only this many songs with the most streams.
+    pub top: Option<usize>,
+}
+
+/// A seeded subset of the filtered songs, stratified by streams when `strata` is set.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SampleConfig {
+    pub size: usize,
+    pub strata: Option<usize>,
+    #[serde(default)]
+    pub seed: u64,
+}
+
+/// Which edges the graph has: `complete`, `threshold` (pairs weighing at least `min_weight`)
+/// or `knn` (the `k` nearest songs of every song).
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct GraphConfig {
+    pub kind: String,
+    pub min_weight: Option<Weight>,
+    pub k: Option<usize>,
+}
+
+impl Default for GraphConfig {
+    fn default() -> Self {
+        GraphConfig { kind: "complete".to_string(), min_weight: None, k: None }
+    }
+}
+
+/// What is computed: the `top` songs under each of `metrics`, and optionally the summary
+/// statistics and a rank comparison of the metrics.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AnalysisConfig {
+    pub metrics: Vec<String>,
+    pub top: usize,
+    pub stats: bool,
+    pub compare: bool,
+}
+
+impl Default for AnalysisConfig {
+    fn default() -> Self {
+        AnalysisConfig { metrics: vec!["closeness".to_string()], top: 10, stats: false, compare: false }
+    }
+}
+
+/// Files written besides the printed report. Unset outputs are skipped.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct OutputConfig {
+    /// Every song's rank under every metric.
+    pub ranking_csv: Option<String>,
+    pub stats_json: Option<String>,
+    pub compare_csv: Option<String>,
+    pub svg: Option<String>,
+    /// `component` or `community`.
+    pub svg_colour: String,
+    /// The SVG's `LayoutOptions::iterations`.
+    pub svg_iterations: usize,
+    /// The SVG's `LayoutOptions::seed`.
+    pub svg_seed: u64,
+    /// The SVG's `LayoutOptions::min_edge_weight`.
+    pub svg_min_edge_weight: Weight,
+    /// The SVG's `LayoutOptions::edges_per_vertex`, 10 by default.
+    pub svg_edges_per_vertex: usize,
+}
+
+impl Default for OutputConfig {
+    fn default() -> Self {
+        let layout = LayoutOptions::default();
+        OutputConfig {
+            ranking_csv: None,
+            stats_json: None,
+            compare_csv: None,
+            svg: None,
+            svg_colour: "component".to_string(),
+            svg_iterations: layout.iterations,
+            svg_seed: layout.seed,
+            svg_min_edge_weight: layout.min_edge_weight,
+            svg_edges_per_vertex: layout.edges_per_vertex,
+        }
+    }
+}
+
+/// A complete analysis run read from a TOML file, with one table per stage. Every table and
+/// key is optional; the defaults reproduce the binary run without arguments. Relative paths
+/// are resolved from the working directory.
+///
+/// ```toml
+/// [input]
+/// path = "Spotify_final_dataset.csv"
+///
+/// [filters]
+/// min_days = 30
+///
+/// [sample]
+/// size = 500
+/// seed = 210
+///
+/// [weights]
+/// peak_position = 0.1
+///
+/// [analysis]
+/// metrics = ["closeness", "harmonic"]
+/// stats = true
+/// ```
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RunConfig {
+    pub input: InputConfig,
+    pub filters: FilterConfig,
+    pub sample: Option<SampleConfig>,
+    /// Coefficients of the edge weight formula.
+    pub weights: WeightParams,
+    pub graph: GraphConfig,
+    pub analysis: AnalysisConfig,
+    pub output: OutputConfig,
+}
+
+fn range<T: Copy>(min: Option<T>, max: Option<T>) -> (Bound<T>, Bound<T>) {
+    (min.map_or(Bound::Unbounded, Bound::Included), max.map_or(Bound::Unbounded, Bound::Included))
+}
+
+fn check_bounds<T: PartialOrd + fmt::Display>(key: &str, min: Option<T>, max: Option<T>) -> Result<(), ConfigError> {
+    match (min, max) {
+        (Some(min), Some(max)) if min > max => Err(ConfigError::at(format!("filters.min_{}", key), format!("{} is above max_{} = {}", min, key, max))),
+        _ => Ok(()),
+    }
+}
+
+impl RunConfig {
+    /// Parses and validates a config. Unknown keys and values of the wrong type are reported
+    /// by the TOML parser with their line and column.
+    pub fn from_toml_str(text: &str) -> Result<Self, ConfigError> {
+        let config: RunConfig = toml::from_str(text).map_err(|e| ConfigError { key: None, message: e.to_string() })?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Reads, parses and validates the config at `path`.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, ConfigError> {
+        let path = path.as_ref();
+        let text = fs::read_to_string(path).map_err(|e| ConfigError { key: None, message: format!("{}: {}", path.display(), e) })?;
+        Self::from_toml_str(&text)
+    }
+
+    /// Checks the settings the types alone cannot, such as metric names and ranges.
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        if self.input.path.trim().is_empty() {
+            return Err(ConfigError::at("input.path", "must not be empty"));
+        }
+        self.column_mapping()?;
+        source_for_format(&self.input.path, &self.format(), ColumnMapping::default()).map_err(|e| ConfigError::at("input.format", e))?;
+
+        let filters = &self.filters;
+        check_bounds("days", filters.min_days, filters.max_days)?;
+        check_bounds("peak", filters.min_peak, filters.max_peak)?;
+        check_bounds("streams", filters.min_streams, filters.max_streams)?;
+        if filters.top == Some(0) {
+            return Err(ConfigError::at("filters.top", "must be at least 1"));
+        }
+
+        if let Some(sample) = &self.sample {
+            if sample.size == 0 {
+                return Err(ConfigError::at("sample.size", "must be at least 1"));
+            }
+            if sample.strata == Some(0) {
+                return Err(ConfigError::at("sample.strata", "must be at least 1"));
+            }
+        }
+
+        let weights = &self.weights;
+        if !(weights.scale.is_finite() && weights.scale > 0.0) {
+            return Err(ConfigError::at("weights.scale", format!("must be positive, got {}", weights.scale)));
+        }
+        for (key, value) in [("peak_position", weights.peak_position), ("total_streams", weights.total_streams), ("days", weights.days)] {
+            if !(value.is_finite() && value >= 0.0) {
+                return Err(ConfigError::at(format!("weights.{}", key), format!("must be zero or positive, got {}", value)));
+            }
+        }
+
+        match self.graph.kind.as_str() {
+            "complete" => {}
+            "threshold" if self.graph.min_weight.is_none() => return Err(ConfigError::at("graph.min_weight", "is required for a threshold graph")),
+            "threshold" => {}
+            "knn" => match self.graph.k {
+                None => return Err(ConfigError::at("graph.k", "is required for a knn graph")),
+                Some(0) => return Err(ConfigError::at("graph.k", "must be at least 1")),
+                Some(_) => {}
+            },
+            other => return Err(ConfigError::at("graph.kind", format!("unknown graph kind `{}`, expected complete, threshold or knn", other))),
+        }
+
+        let metrics = self.metrics()?;
+        if metrics.is_empty() {
+            return Err(ConfigError::at("analysis.metrics", "must name at least one metric"));
+        }
+        if self.analysis.top == 0 {
+            return Err(ConfigError::at("analysis.top", "must be at least 1"));
+        }
+        if self.analysis.compare && metrics.len() < 2 {
+            return Err(ConfigError::at("analysis.compare", "needs at least two metrics in analysis.metrics"));
+        }
+        if self.output.compare_csv.is_some() && !self.analysis.compare {
+            return Err(ConfigError::at("output.compare_csv", "needs analysis.compare = true"));
+        }
+        self.colouring()?;
+        Ok(())
+    }
+
+    fn format(&self) -> String {
+        self.input.format.clone().unwrap_or_else(|| format_for_path(&self.input.path).to_string())
+    }
+
+    fn column_mapping(&self) -> Result<ColumnMapping, ConfigError> {
+        let mut mapping = ColumnMapping::default();
+        for (field, name) in &self.input.columns {
+            let key = format!("input.columns.{}", field);
+            let column = match name.strip_prefix('#') {
+                Some(index) => Column::Index(index.parse().map_err(|_| ConfigError::at(&key, format!("invalid column index `{}`", name)))?),
+                None => Column::Name(name.clone()),
+            };
+            mapping.set(field, column).map_err(|e| ConfigError::at(&key, e.to_string()))?;
+        }
+        Ok(mapping)
+    }
+
+    /// The metrics of `analysis.metrics`, in order.
+    pub fn metrics(&self) -> Result<Vec<Centrality>, ConfigError> {
+        self.analysis
+            .metrics
+            .iter()
+            .enumerate()
+            .map(|(i, name)| name.parse().map_err(|e| ConfigError::at(format!("analysis.metrics[{}]", i), e)))
+            .collect()
+    }
+
+    fn colouring(&self) -> Result<Colouring, ConfigError> {
+        self.output.svg_colour.parse().map_err(|e| ConfigError::at("output.svg_colour", e))
+    }
+
+    /// The input file as a `SongSource`.
+    pub fn source(&self) -> Result<Box<dyn SongSource>, ConfigError> {
+        source_for_format(&self.input.path, &self.format(), self.column_mapping()?).map_err(|e| ConfigError::at("input.format", e))
+    }
+
+    /// The `filters` table as a `SongQuery`.
+    pub fn query(&self) -> SongQuery {
+        let filters = &self.filters;
+        let mut query = SongQuery::new()
+            .days(range(filters.min_days, filters.max_days))
+            .peak_position(range(filters.min_peak, filters.max_peak))
+            .total_streams(range(filters.min_streams, filters.max_streams))
+            .exclude_artists(filters.exclude_artists.iter());
+        if !filters.include_artists.is_empty() {
+            query = query.include_artists(filters.include_artists.iter());
+        }
+        if let Some(text) = &filters.artist_contains {
+            query = query.artist_name_contains(text);
+        }
+        if let Some(text) = &filters.song_contains {
+            query = query.song_name_contains(text);
+        }
+        if let Some(n) = filters.top {
+            query = query.top_by_streams(n);
+        }
+        query
+    }
+
+    /// Loads, filters and samples the songs the graph is built over.
+    pub fn songs(&self) -> Result<Vec<Song>, Box<dyn Error>> {
+        let query = self.query();
+        let mut songs = Vec::new();
+        for result in self.source()?.songs()? {
+            let song = result?;
+            if query.matches(&song) {
+                songs.push(song);
+            }
+        }
+        let songs = query.apply(songs);
+        Ok(match &self.sample {
+            Some(SampleConfig { size, strata: Some(strata), seed }) => stratified_subset(&songs, *size, *strata, *seed),
+            Some(SampleConfig { size, strata: None, seed }) => uniform_subset(&songs, *size, *seed),
+            None => songs,
+        })
+    }
+
+    /// Builds the graph described by the `input`, `filters`, `sample`, `weights` and `graph`
+    /// tables. Filters that leave no songs are an error keyed at `filters`.
+    pub fn build_graph(&self) -> Result<Graph, Box<dyn Error>> {
+        self.build_graph_observed(&Silent)
+    }
+
+    /// `build_graph` reporting the edge building to `progress`. A cancelled build returns the
+    /// partial graph, as the `_observed` graph builders do.
+    pub fn build_graph_observed(&self, progress: &dyn Progress) -> Result<Graph, Box<dyn Error>> {
+        let songs = self.songs()?;
+        if songs.is_empty() {
+            return Err(ConfigError::at("filters", format!("no songs in {} match the filters", self.input.path)).into());
+        }
+        Ok(match (self.graph.kind.as_str(), self.graph.k) {
+            ("knn", Some(k)) => Graph::build_knn_with_weights_observed(songs, k, &self.weights, progress),
+            ("threshold", _) => Graph::build_with_weights_observed(songs, &self.weights, self.graph.min_weight, progress),
+            _ => Graph::build_with_weights_observed(songs, &self.weights, None, progress),
+        })
+    }
+
+    /// Builds the graph, writes the report for the `analysis` table to `out` and writes every
+    /// file named in `output`.
+    pub fn run(&self, out: &mut dyn Write) -> Result<(), Box<dyn Error>> {
+        self.run_observed(out, &Silent)
+    }
+
+    /// `run` reporting every long step to `progress`: the graph build, the rankings, the
+    /// statistics, the comparison and the SVG layout. Cancelling is an error that names the
+    /// step. A cancelled ranking is still reported and written as far as it got; the steps
+    /// after it are skipped.
+    pub fn run_observed(&self, out: &mut dyn Write, progress: &dyn Progress) -> Result<(), Box<dyn Error>> {
+        let graph = self.build_graph_observed(progress)?;
+        if progress.is_cancelled() {
+            return Err("cancelled while building the graph".into());
+        }
+        let edges: usize = graph.adjacency_list.iter().map(Vec::len).sum::<usize>() / 2;
+        writeln!(out, "{:<24} {}", "Songs", graph.vertices.len())?;
+        writeln!(out, "{:<24} {}", "Edges", edges)?;
+        if !graph.is_connected() {
+            writeln!(out, "Warning: graph is not connected, closeness only covers reachable songs")?;
+        }
+
+        let metrics = self.metrics()?;
+        let mut ranking_writer = match &self.output.ranking_csv {
+            Some(path) => {
+                let mut writer = Writer::from_path(path)?;
+                writer.write_record(["metric", "rank", "dense_rank", "artist_name", "song_name", "score", "total_streams"])?;
+                Some(writer)
+            }
+            None => None,
+        };
+        for &metric in &metrics {
+            let ranking = graph.ranking_observed(metric, progress);
+            writeln!(out, "Most central by {}:", metric)?;
+            for entry in ranking.iter().take(self.analysis.top) {
+                writeln!(out, "Depth {}: Song: {}, Artist: {}, {}: {:.4}", entry.competition_rank, entry.song_name, entry.artist_name, metric, entry.score)?;
+            }
+            if let Some(writer) = ranking_writer.as_mut() {
+                for entry in &ranking {
+                    writer.write_record([
+                        metric.name().to_string(),
+                        entry.competition_rank.to_string(),
+                        entry.dense_rank.to_string(),
+                        entry.artist_name.clone(),
+                        entry.song_name.clone(),
+                        format!("{:.6}", entry.score),
+                        entry.total_streams.to_string(),
+                    ])?;
+                }
+            }
+            if progress.is_cancelled() {
+                writeln!(out, "Cancelled: only {} of {} songs were scored", ranking.len(), graph.vertices.len())?;
+                break;
+            }
+        }
+        if let Some(mut writer) = ranking_writer {
+            writer.flush()?;
+        }
+        if progress.is_cancelled() {
+            return Err("cancelled while ranking".into());
+        }
+
+        if self.analysis.stats || self.output.stats_json.is_some() {
+            let stats = graph.stats_observed(progress);
+            if progress.is_cancelled() {
+                return Err("cancelled while computing statistics".into());
+            }
+            if self.analysis.stats {
+                write!(out, "{}", stats)?;
+            }
+            if let Some(path) = &self.output.stats_json {
+                fs::write(path, stats.to_json()?)?;
+            }
+        }
+
+        if self.analysis.compare {
+            let comparison = graph.compare_centrality_observed(&metrics, progress);
+            if progress.is_cancelled() {
+                return Err("cancelled while comparing measures".into());
+            }
+            write!(out, "{}", comparison)?;
+            if let Some(path) = &self.output.compare_csv {
+                comparison.write_csv(path)?;
+            }
+        }
+
+        if let Some(path) = &self.output.svg {
+            let output = &self.output;
+            let options = LayoutOptions {
+                iterations: output.svg_iterations,
+                seed: output.svg_seed,
+                min_edge_weight: output.svg_min_edge_weight,
+                edges_per_vertex: output.svg_edges_per_vertex,
+                ..LayoutOptions::default()
+            };
+            graph.write_svg_observed(path, &options, self.colouring()?, progress)?;
+        }
+        Ok(())
+    }
+}
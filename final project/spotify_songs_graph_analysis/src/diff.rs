@@ -0,0 +1,170 @@
+use super::centrality::Centrality;
+use super::graph::{Graph, Vertex, Weight};
+use super::progress::{Progress, Silent};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+
+/// Artist and song name, trimmed, identifying the same song in two graphs.
+pub type SongKey = (String, String);
+
+/// An edge between two songs present in both graphs whose weight differs by more than the
+/// tolerance. `None` means the edge is missing on that side.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EdgeChange {
+    pub from: SongKey,
+    pub to: SongKey,
+    pub old_weight: Option<Weight>,
+    pub new_weight: Option<Weight>,
+}
+
+/// A song's centrality rank in both graphs. Positive `movement` means it rose.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RankMove {
+    pub song: SongKey,
+    pub old_rank: usize,
+    pub new_rank: usize,
+    pub movement: isize,
+}
+
+/// What changed between an old and a new graph.
+#[derive(Debug, Clone)]
+pub struct GraphDiff {
+    /// Songs only in the new graph, in its vertex order.
+    pub added: Vec<SongKey>,
+    /// Songs only in the old graph, in its vertex order.
+    pub removed: Vec<SongKey>,
+    pub changed_edges: Vec<EdgeChange>,
+    /// Metric the ranks were compared under, if any.
+    pub metric: Option<Centrality>,
+    /// Songs in both graphs ordered by how far their rank moved, largest first.
+    pub rank_moves: Vec<RankMove>,
+}
+
+fn key(graph: &Graph, vertex: Vertex) -> SongKey {
+    let song = &graph.vertices[vertex];
+    (song.artist_name.trim().to_string(), song.song_name.trim().to_string())
+}
+
+// Vertex of every key; songs listed twice are matched by their first vertex.
+fn vertices_by_key(graph: &Graph) -> HashMap<SongKey, Vertex> {
+    let mut vertices = HashMap::new();
+    for vertex in 0..graph.vertices.len() {
+        vertices.entry(key(graph, vertex)).or_insert(vertex);
+    }
+    vertices
+}
+
+impl Graph {
+    /// Compares `self`, the old graph, with `new`, matching songs by trimmed artist and song
+    /// name. Edges between songs in both graphs are reported when their weights differ by
+    /// more than `tolerance` or the edge exists on one side only. When `metric` is given,
+    /// both graphs are ranked by it and the rank movements of shared songs are reported.
+    pub fn diff(&self, new: &Graph, tolerance: Weight, metric: Option<Centrality>) -> GraphDiff {
+        self.diff_observed(new, tolerance, metric, &Silent)
+    }
+
+    /// `diff` reporting the two rankings to `progress`. If `progress` cancels them, rank
+    /// movements only cover the songs scored in both graphs.
+    pub fn diff_observed(&self, new: &Graph, tolerance: Weight, metric: Option<Centrality>, progress: &dyn Progress) -> GraphDiff {
+        let old_keys = vertices_by_key(self);
+        let new_keys = vertices_by_key(new);
+
+        let added = (0..new.vertices.len())
+            .filter(|&v| new_keys[&key(new, v)] == v && !old_keys.contains_key(&key(new, v)))
+            .map(|v| key(new, v))
+            .collect();
+        let removed = (0..self.vertices.len())
+            .filter(|&v| old_keys[&key(self, v)] == v && !new_keys.contains_key(&key(self, v)))
+            .map(|v| key(self, v))
+            .collect();
+
+        // old vertex of every matched new vertex
+        let to_old: HashMap<Vertex, Vertex> =
+            new_keys.iter().filter_map(|(song, &v)| old_keys.get(song).map(|&u| (v, u))).collect();
+        let mut matched = vec![false; self.vertices.len()];
+        for &u in to_old.values() {
+            matched[u] = true;
+        }
+
+        let mut weights: BTreeMap<(Vertex, Vertex), (Option<Weight>, Option<Weight>)> = BTreeMap::new();
+        for (u, adj_list) in self.adjacency_list.iter().enumerate().filter(|&(u, _)| matched[u]) {
+            for &(v, weight) in adj_list.iter().filter(|&&(v, _)| u < v && matched[v]) {
+                weights.entry((u, v)).or_insert((None, None)).0 = Some(weight);
+            }
+        }
+        for (a, adj_list) in new.adjacency_list.iter().enumerate() {
+            let Some(&u) = to_old.get(&a) else { continue };
+            for &(b, weight) in adj_list {
+                if let Some(&v) = to_old.get(&b) {
+                    if u < v {
+                        weights.entry((u, v)).or_insert((None, None)).1 = Some(weight);
+                    }
+                }
+            }
+        }
+
+        let changed_edges = weights
+            .into_iter()
+            .filter(|(_, (old, new))| match (old, new) {
+                (Some(old), Some(new)) => (old - new).abs() > tolerance,
+                _ => true,
+            })
+            .map(|((u, v), (old_weight, new_weight))| EdgeChange { from: key(self, u), to: key(self, v), old_weight, new_weight })
+            .collect();
+
+        let rank_moves = metric.map_or_else(Vec::new, |metric| {
+            let old_ranks: HashMap<Vertex, usize> = self.ranking_observed(metric, progress).into_iter().map(|e| (e.vertex, e.competition_rank)).collect();
+            let mut moves: Vec<RankMove> = new
+                .ranking_observed(metric, progress)
+                .into_iter()
+                .filter_map(|entry| {
+                    let old_rank = *old_ranks.get(to_old.get(&entry.vertex)?)?;
+                    Some(RankMove {
+                        song: key(new, entry.vertex),
+                        old_rank,
+                        new_rank: entry.competition_rank,
+                        movement: old_rank as isize - entry.competition_rank as isize,
+                    })
+                })
+                .collect();
+            moves.sort_by(|a, b| b.movement.abs().cmp(&a.movement.abs()).then_with(|| a.song.cmp(&b.song)));
+            moves
+        });
+
+        GraphDiff { added, removed, changed_edges, metric, rank_moves }
+    }
+}
+
+impl fmt::Display for GraphDiff {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "{:<24} {}", "Added songs", self.added.len())?;
+        writeln!(f, "{:<24} {}", "Removed songs", self.removed.len())?;
+        writeln!(f, "{:<24} {}", "Changed edges", self.changed_edges.len())?;
+        for (title, songs) in [("Added", &self.added), ("Removed", &self.removed)] {
+            if !songs.is_empty() {
+                writeln!(f, "{}:", title)?;
+                for (artist_name, song_name) in songs.iter().take(10) {
+                    writeln!(f, "  Song: {}, Artist: {}", song_name, artist_name)?;
+                }
+            }
+        }
+        let weight = |weight: Option<Weight>| weight.map_or("-".to_string(), |weight| weight.to_string());
+        if !self.changed_edges.is_empty() {
+            writeln!(f, "Changed edges:")?;
+            for change in self.changed_edges.iter().take(10) {
+                writeln!(f, "  {} / {}: {} -> {}", change.from.1, change.to.1, weight(change.old_weight), weight(change.new_weight))?;
+            }
+        }
+        if let Some(metric) = self.metric {
+            writeln!(f, "Largest {} rank moves:", metric)?;
+            for rank_move in self.rank_moves.iter().take(10) {
+                writeln!(
+                    f,
+                    "  Song: {}, Artist: {}, Rank: {} -> {} ({:+})",
+                    rank_move.song.1, rank_move.song.0, rank_move.old_rank, rank_move.new_rank, rank_move.movement
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,63 @@
+use super::graph::{Graph, Vertex, Weight};
+use std::collections::{HashMap, VecDeque};
+
+impl Graph {
+    /// Strength of `vertex`, the sum of the weights of its edges. Weights here are read as
+    /// similarities, so a higher strength means a song closer to the rest, even though
+    /// `dijkstra` and the centrality measures treat the same weights as lengths. On a complete
+    /// graph every vertex has the same degree, but strength still tells the songs apart.
+    pub fn strength(&self, vertex: Vertex) -> Weight {
+        self.adjacency_list[vertex].iter().map(|&(_, weight)| weight).sum()
+    }
+
+    /// Strength of every vertex, by vertex.
+    pub fn strengths(&self) -> Vec<Weight> {
+        (0..self.vertices.len()).map(|vertex| self.strength(vertex)).collect()
+    }
+
+    /// Vertices within `hops` edges of `center`, in breadth first order starting with `center`.
+    /// With `min_weight`, only edges at least that heavy are followed.
+    pub fn ego_vertices(&self, center: Vertex, hops: usize, min_weight: Option<Weight>) -> Vec<Vertex> {
+        let mut depth = vec![usize::MAX; self.vertices.len()];
+        let mut order = vec![center];
+        let mut queue = VecDeque::from([center]);
+        depth[center] = 0;
+
+        while let Some(u) = queue.pop_front() {
+            if depth[u] == hops {
+                continue;
+            }
+            for &(v, weight) in &self.adjacency_list[u] {
+                if depth[v] == usize::MAX && min_weight.is_none_or(|min_weight| weight >= min_weight) {
+                    depth[v] = depth[u] + 1;
+                    order.push(v);
+                    queue.push_back(v);
+                }
+            }
+        }
+        order
+    }
+
+    /// The `hops`-hop ego network of `center` as a new graph: the songs `ego_vertices` finds
+    /// and every edge between them, skipping edges lighter than `min_weight` when given. Vertex
+    /// `i` of the result is `ego_vertices(center, hops, min_weight)[i]`, so `center` is vertex 0.
+    pub fn ego_network(&self, center: Vertex, hops: usize, min_weight: Option<Weight>) -> Graph {
+        let members = self.ego_vertices(center, hops, min_weight);
+        let position: HashMap<Vertex, Vertex> = members.iter().enumerate().map(|(i, &v)| (v, i)).collect();
+
+        let mut ego = Graph::new();
+        for &v in &members {
+            ego.add_vertex(self.vertices[v].clone());
+        }
+        for (i, &u) in members.iter().enumerate() {
+            for &(v, weight) in &self.adjacency_list[u] {
+                if let Some(&j) = position.get(&v) {
+                    if i < j && min_weight.is_none_or(|min_weight| weight >= min_weight) {
+                        ego.add_edge(i, j, weight);
+                    }
+                }
+            }
+        }
+        ego
+    }
+}
@@ -0,0 +1,260 @@
+use super::graph::{Graph, Vertex};
+use csv::Writer;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use std::error::Error;
+use std::str::FromStr;
+
+/// One vector per vertex, all of the same length, in vertex order.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Embedding {
+    pub vectors: Vec<Vec<f64>>,
+}
+
+impl Embedding {
+    /// Length of every vector.
+    pub fn dimensions(&self) -> usize {
+        self.vectors.first().map_or(0, |vector| vector.len())
+    }
+
+    /// Writes one row per song of `graph`: artist, song name, then the `d0 .. d{n-1}` coordinates.
+    pub fn write_csv(&self, graph: &Graph, file_path: &str) -> Result<(), Box<dyn Error>> {
+        let mut writer = Writer::from_path(file_path)?;
+        let mut header = vec!["artist_name".to_string(), "song_name".to_string()];
+        header.extend((0..self.dimensions()).map(|i| format!("d{}", i)));
+        writer.write_record(&header)?;
+
+        for (song, vector) in graph.vertices.iter().zip(&self.vectors) {
+            let mut row = vec![song.artist_name.clone(), song.song_name.clone()];
+            row.extend(vector.iter().map(|value| value.to_string()));
+            writer.write_record(&row)?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+}
+
+/// How songs are embedded, as chosen on the command line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EmbeddingMethod {
+    Spectral,
+    DeepWalk,
+}
+
+impl FromStr for EmbeddingMethod {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        match text {
+            "spectral" => Ok(EmbeddingMethod::Spectral),
+            "deepwalk" => Ok(EmbeddingMethod::DeepWalk),
+            _ => Err(format!("unknown embedding `{}`, expected spectral or deepwalk", text)),
+        }
+    }
+}
+
+/// Settings for `Graph::deepwalk`.
+#[derive(Debug, Clone)]
+pub struct DeepWalkOptions {
+    pub dimensions: usize,
+    pub walks_per_vertex: usize,
+    pub walk_length: usize,
+    /// Songs up to this many steps apart on a walk count as context for each other.
+    pub window: usize,
+    /// Negative samples drawn per context pair.
+    pub negative: usize,
+    pub epochs: usize,
+    pub learning_rate: f64,
+    pub seed: u64,
+}
+
+impl Default for DeepWalkOptions {
+    fn default() -> Self {
+        DeepWalkOptions {
+            dimensions: 64,
+            walks_per_vertex: 10,
+            walk_length: 40,
+            window: 5,
+            negative: 5,
+            epochs: 1,
+            learning_rate: 0.025,
+            seed: 0,
+        }
+    }
+}
+
+impl Graph {
+    /// Spectral embedding from the normalised Laplacian `I - D^-1/2 W D^-1/2`, with edge
+    /// weights as `W`. Row `v` holds the entries for `v` of the eigenvectors with the
+    /// `dimensions` smallest non-trivial eigenvalues, scaled by `D^-1/2` so that they
+    /// approximate the random walk Laplacian's eigenvectors. Found by seeded subspace
+    /// iteration on `I + D^-1/2 W D^-1/2`, which shares the eigenvectors with the order
+    /// reversed; more `iterations` give more accurate vectors. Isolated vertices get zero rows.
+    pub fn spectral_embedding(&self, dimensions: usize, iterations: usize, seed: u64) -> Embedding {
+        let n = self.vertices.len();
+        let degrees: Vec<f64> =
+            self.adjacency_list.iter().map(|adj_list| adj_list.iter().map(|&(_, weight)| weight.max(0) as f64).sum()).collect();
+        let inverse_sqrt: Vec<f64> = degrees.iter().map(|&degree| if degree > 0.0 { 1.0 / degree.sqrt() } else { 0.0 }).collect();
+
+        // x -> x + D^-1/2 W D^-1/2 x, restricted to vertices with edges
+        let multiply = |x: &[f64]| -> Vec<f64> {
+            let mut y: Vec<f64> = (0..n).map(|v| if degrees[v] > 0.0 { x[v] } else { 0.0 }).collect();
+            for (u, adj_list) in self.adjacency_list.iter().enumerate() {
+                for &(v, weight) in adj_list {
+                    y[u] += inverse_sqrt[u] * weight.max(0) as f64 * inverse_sqrt[v] * x[v];
+                }
+            }
+            y
+        };
+
+        // the trivial eigenvector D^1/2 1 is known and kept fixed
+        let mut trivial: Vec<f64> = degrees.iter().map(|degree| degree.sqrt()).collect();
+        normalise(&mut trivial);
+
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut basis: Vec<Vec<f64>> =
+            (0..dimensions.min(n.saturating_sub(1))).map(|_| (0..n).map(|_| rng.gen_range(-1.0..1.0)).collect()).collect();
+        orthonormalise(&mut basis, &trivial);
+        for _ in 0..iterations {
+            basis = basis.iter().map(|vector| multiply(vector)).collect();
+            orthonormalise(&mut basis, &trivial);
+        }
+
+        let vectors = (0..n)
+            .map(|v| {
+                let mut row: Vec<f64> = basis.iter().map(|vector| vector[v] * inverse_sqrt[v]).collect();
+                row.resize(dimensions, 0.0);
+                row
+            })
+            .collect();
+        Embedding { vectors }
+    }
+
+    /// DeepWalk: truncated random walks from every vertex, stepping to a neighbour with
+    /// probability proportional to the edge weight, fed to a skip-gram model trained with
+    /// negative sampling. Seeded, so the same options give the same vectors.
+    pub fn deepwalk(&self, options: &DeepWalkOptions) -> Embedding {
+        let n = self.vertices.len();
+        let mut rng = StdRng::seed_from_u64(options.seed);
+        let walks = self.random_walks(options.walks_per_vertex, options.walk_length, &mut rng);
+
+        // negative samples follow vertex frequency in the walks raised to 3/4, as in word2vec
+        let mut frequency = vec![0.0; n];
+        for &vertex in walks.iter().flatten() {
+            frequency[vertex] += 1.0;
+        }
+        let noise = cumulative(frequency.iter().map(|f: &f64| f.powf(0.75)));
+
+        let d = options.dimensions;
+        let mut input: Vec<Vec<f64>> = (0..n).map(|_| (0..d).map(|_| (rng.gen::<f64>() - 0.5) / d as f64).collect()).collect();
+        let mut output: Vec<Vec<f64>> = vec![vec![0.0; d]; n];
+
+        let total_steps = (options.epochs * walks.len()).max(1);
+        let mut step = 0;
+        let mut gradient = vec![0.0; d];
+        for _ in 0..options.epochs {
+            for walk in &walks {
+                let learning_rate = (options.learning_rate * (1.0 - step as f64 / total_steps as f64)).max(options.learning_rate * 1e-4);
+                step += 1;
+                for (i, &centre) in walk.iter().enumerate() {
+                    let start = i.saturating_sub(options.window);
+                    let end = (i + options.window + 1).min(walk.len());
+                    for &context in &walk[start..end] {
+                        if context == centre {
+                            continue;
+                        }
+                        gradient.iter_mut().for_each(|g| *g = 0.0);
+                        let samples = std::iter::once((context, 1.0))
+                            .chain((0..options.negative).filter_map(|_| sample(&noise, &mut rng)).filter(|&v| v != context).map(|v| (v, 0.0)));
+                        for (target, label) in samples {
+                            let dot: f64 = input[centre].iter().zip(&output[target]).map(|(a, b)| a * b).sum();
+                            let g = (label - sigmoid(dot)) * learning_rate;
+                            for k in 0..d {
+                                gradient[k] += g * output[target][k];
+                                output[target][k] += g * input[centre][k];
+                            }
+                        }
+                        for k in 0..d {
+                            input[centre][k] += gradient[k];
+                        }
+                    }
+                }
+            }
+        }
+        Embedding { vectors: input }
+    }
+
+    fn random_walks(&self, walks_per_vertex: usize, walk_length: usize, rng: &mut StdRng) -> Vec<Vec<Vertex>> {
+        let transitions: Vec<Vec<f64>> = self
+            .adjacency_list
+            .iter()
+            .map(|adj_list| {
+                let weights = cumulative(adj_list.iter().map(|&(_, weight)| weight.max(0) as f64));
+                // fall back to uniform steps when every weight is zero
+                if weights.last().is_some_and(|&total| total > 0.0) {
+                    weights
+                } else {
+                    cumulative(adj_list.iter().map(|_| 1.0))
+                }
+            })
+            .collect();
+
+        let mut walks = vec![];
+        for _ in 0..walks_per_vertex {
+            for start in 0..self.vertices.len() {
+                let mut walk = vec![start];
+                while walk.len() < walk_length.max(1) {
+                    let current = walk[walk.len() - 1];
+                    match sample(&transitions[current], rng) {
+                        Some(index) => walk.push(self.adjacency_list[current][index].0),
+                        None => break,
+                    }
+                }
+                walks.push(walk);
+            }
+        }
+        walks
+    }
+}
+
+fn cumulative(weights: impl Iterator<Item = f64>) -> Vec<f64> {
+    weights
+        .scan(0.0, |total, weight| {
+            *total += weight;
+            Some(*total)
+        })
+        .collect()
+}
+
+// Index drawn with probability proportional to its share of the cumulative total.
+fn sample(cumulative: &[f64], rng: &mut StdRng) -> Option<usize> {
+    let total = *cumulative.last()?;
+    if total <= 0.0 {
+        return None;
+    }
+    let point = rng.gen_range(0.0..total);
+    Some(cumulative.partition_point(|&c| c <= point).min(cumulative.len() - 1))
+}
+
+fn sigmoid(x: f64) -> f64 {
+    1.0 / (1.0 + (-x.clamp(-30.0, 30.0)).exp())
+}
+
+fn normalise(vector: &mut [f64]) {
+    let norm = vector.iter().map(|x| x * x).sum::<f64>().sqrt();
+    if norm > 0.0 {
+        vector.iter_mut().for_each(|x| *x /= norm);
+    }
+}
+
+// Modified Gram-Schmidt, first against `fixed` and then against the earlier vectors.
+fn orthonormalise(basis: &mut [Vec<f64>], fixed: &[f64]) {
+    for i in 0..basis.len() {
+        let (done, rest) = basis.split_at_mut(i);
+        let vector = &mut rest[0];
+        for other in std::iter::once(fixed).chain(done.iter().map(|v| v.as_slice())) {
+            let dot: f64 = vector.iter().zip(other).map(|(a, b)| a * b).sum();
+            vector.iter_mut().zip(other).for_each(|(a, b)| *a -= dot * b);
+        }
+        normalise(vector);
+    }
+}
@@ -0,0 +1,172 @@
+use super::graph::{Graph, Vertex, Weight};
+use super::song::Song;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use std::collections::BTreeSet;
+
+/// How the edges of a generated graph are weighted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WeightSpec {
+    /// Every edge gets the same weight.
+    Constant(Weight),
+    /// Weights drawn uniformly from the inclusive range between the two bounds, given in
+    /// either order.
+    Uniform(Weight, Weight),
+}
+
+/// Builds graphs of known structure with seeded randomness. Vertices are placeholder songs
+/// named `node <index>` by the artist `synthetic`.
+pub struct Generator {
+    rng: StdRng,
+    weights: WeightSpec,
+}
+
+impl Generator {
+    /// Generator seeded with `seed` whose edges are weighted according to `weights`.
+    pub fn new(seed: u64, weights: WeightSpec) -> Self {
+        let weights = match weights {
+            WeightSpec::Uniform(low, high) => WeightSpec::Uniform(low.min(high), low.max(high)),
+            constant => constant,
+        };
+        Generator {
+            rng: StdRng::seed_from_u64(seed),
+            weights,
+        }
+    }
+
+    /// G(n, p): every pair of vertices is connected independently with probability `p`.
+    pub fn erdos_renyi(&mut self, n: usize, p: f64) -> Graph {
+        let mut edges = vec![];
+        for u in 0..n {
+            for v in (u + 1)..n {
+                if self.rng.gen_bool(p.clamp(0.0, 1.0)) {
+                    edges.push((u, v));
+                }
+            }
+        }
+        self.build(n, edges)
+    }
+
+    /// Preferential attachment: starts from a clique of `m + 1` vertices and attaches every
+    /// further vertex to `m` distinct existing vertices chosen with probability proportional
+    /// to their degree.
+    pub fn barabasi_albert(&mut self, n: usize, m: usize) -> Graph {
+        let m = m.max(1);
+        let initial = (m + 1).min(n);
+        let mut edges = vec![];
+        // every vertex appears once per incident edge, so uniform picks follow the degrees
+        let mut endpoints: Vec<Vertex> = vec![];
+        for u in 0..initial {
+            for v in (u + 1)..initial {
+                edges.push((u, v));
+                endpoints.extend([u, v]);
+            }
+        }
+
+        for u in initial..n {
+            let mut targets = BTreeSet::new();
+            while targets.len() < m {
+                targets.insert(endpoints[self.rng.gen_range(0..endpoints.len())]);
+            }
+            for v in targets {
+                edges.push((v, u));
+                endpoints.extend([u, v]);
+            }
+        }
+        self.build(n, edges)
+    }
+
+    /// Ring lattice where each vertex links to its `k / 2` nearest neighbours on both sides,
+    /// after which every edge is rewired to a random endpoint with probability `beta`.
+    pub fn watts_strogatz(&mut self, n: usize, k: usize, beta: f64) -> Graph {
+        let half = (k / 2).min(n.saturating_sub(1) / 2);
+        let mut edges: BTreeSet<(Vertex, Vertex)> = BTreeSet::new();
+        let mut degree = vec![0; n];
+        for u in 0..n {
+            for j in 1..=half {
+                let v = (u + j) % n;
+                if edges.insert((u.min(v), u.max(v))) {
+                    degree[u] += 1;
+                    degree[v] += 1;
+                }
+            }
+        }
+
+        for u in 0..n {
+            for j in 1..=half {
+                let v = (u + j) % n;
+                let edge = (u.min(v), u.max(v));
+                if !self.rng.gen_bool(beta.clamp(0.0, 1.0)) || !edges.contains(&edge) {
+                    continue;
+                }
+                // a vertex already linked to everything keeps its edge
+                if degree[u] == n - 1 {
+                    continue;
+                }
+                let w = loop {
+                    let w = self.rng.gen_range(0..n);
+                    if w != u && !edges.contains(&(u.min(w), u.max(w))) {
+                        break w;
+                    }
+                };
+                edges.remove(&edge);
+                edges.insert((u.min(w), u.max(w)));
+                degree[v] -= 1;
+                degree[w] += 1;
+            }
+        }
+        self.build(n, edges.into_iter().collect())
+    }
+
+    /// `rows` by `cols` lattice; vertex `r * cols + c` sits at row `r`, column `c`.
+    pub fn grid(&mut self, rows: usize, cols: usize) -> Graph {
+        let mut edges = vec![];
+        for r in 0..rows {
+            for c in 0..cols {
+                let u = r * cols + c;
+                if c + 1 < cols {
+                    edges.push((u, u + 1));
+                }
+                if r + 1 < rows {
+                    edges.push((u, u + cols));
+                }
+            }
+        }
+        self.build(rows * cols, edges)
+    }
+
+    /// Vertex 0 connected to each of the other `n - 1` vertices.
+    pub fn star(&mut self, n: usize) -> Graph {
+        self.build(n, (1..n).map(|v| (0, v)).collect())
+    }
+
+    /// Vertices 0 to `n - 1` connected in order.
+    pub fn path(&mut self, n: usize) -> Graph {
+        self.build(n, (1..n).map(|v| (v - 1, v)).collect())
+    }
+
+    /// Every pair of the `n` vertices connected.
+    pub fn complete(&mut self, n: usize) -> Graph {
+        self.erdos_renyi(n, 1.0)
+    }
+
+    fn build(&mut self, n: usize, edges: Vec<(Vertex, Vertex)>) -> Graph {
+        let mut graph = Graph::new();
+        for i in 0..n {
+            graph.add_vertex(Song {
+                artist_name: "synthetic".to_string(),
+                song_name: format!("node {}", i),
+                days: 0,
+                peak_position: 0,
+                total_streams: 0,
+            });
+        }
+        for (u, v) in edges {
+            let weight = match self.weights {
+                WeightSpec::Constant(weight) => weight,
+                WeightSpec::Uniform(low, high) => self.rng.gen_range(low..=high),
+            };
+            graph.add_edge(u, v, weight);
+        }
+        graph
+    }
+}
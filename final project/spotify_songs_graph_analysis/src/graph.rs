@@ -1,19 +1,38 @@
-use super::song::Song;
+use super::centrality::Centrality;
+use super::song::{stream_songs_from_csv, Song};
+use super::progress::{Progress, Silent};
+use super::source::SongSource;
+use super::weights::feature_weight;
 use std::collections::{HashMap, BinaryHeap};
+use std::error::Error;
 use std::cmp::Reverse;
 use ordered_float::OrderedFloat;
 
+/// Index of a song in `Graph::vertices`.
 pub type Vertex = usize;
+/// Edge weight. Algorithms treat it as a distance.
 pub type Weight = isize; 
+/// Neighbours and edge weights of every vertex.
 pub type AdjacencyLists = Vec<Vec<(Vertex, Weight)>>;
+/// Centrality of every vertex, keyed by vertex, with its song name, artist name and score.
+pub type CentralityScores = HashMap<usize, (String, String, f32)>;
 
 
+/// Undirected weighted graph with one vertex per song. Every edge is stored in both
+/// endpoints' adjacency lists.
 pub struct Graph {
     pub vertices: Vec<Song>, 
     pub adjacency_list: AdjacencyLists, 
 }
 
+impl Default for Graph {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Graph {
+    /// Creates an empty graph.
     pub fn new() -> Self {
         Graph {
             vertices: vec![],
@@ -21,6 +40,7 @@ impl Graph {
         }
     }
 
+    /// Adds a song and returns its vertex index.
     pub fn add_vertex(&mut self, song: Song) -> usize {
         let index = self.vertices.len();
         self.vertices.push(song);
@@ -28,40 +48,104 @@ impl Graph {
         index
     }
 
-    pub fn add_weighted_edge_by_features(&mut self, src: usize, dest: usize) {
-        let song_a = &self.vertices[src];
-        let song_b = &self.vertices[dest];
-    
+    /// Adds an undirected edge. Self loops are ignored.
+    pub fn add_edge(&mut self, src: Vertex, dest: Vertex, weight: Weight) {
         if src != dest {
-            let peak_position_diff = (song_a.peak_position as isize - song_b.peak_position as isize).abs();
-            let stream_diff = (song_a.total_streams as isize - song_b.total_streams as isize).abs();
-            let days_diff = (song_a.days as isize - song_b.days as isize).abs();
-    
-            let weight = 1000.0 / (1.0 + 0.05 * peak_position_diff as f32 + 0.01 * stream_diff as f32 / 1000000.0 + 0.1 * days_diff as f32 / 10.0);
-            
-            self.adjacency_list[src].push((dest, weight as isize));
-            self.adjacency_list[dest].push((src, weight as isize));
+            self.adjacency_list[src].push((dest, weight));
+            self.adjacency_list[dest].push((src, weight));
         }
     }
+
+    /// Connects two songs with a weight derived from how similar their peak position,
+    /// total streams and days on the chart are.
+    pub fn add_weighted_edge_by_features(&mut self, src: usize, dest: usize) {
+        let weight = feature_weight(&self.vertices[src], &self.vertices[dest]);
+        self.add_edge(src, dest, weight);
+    }
     
+    /// Builds the complete song graph, weighting every pair with `add_weighted_edge_by_features`.
     pub fn build_from_songs(songs: Vec<Song>) -> Self {
+        Self::build_from_iter(songs)
+    }
+
+    /// Same as `build_from_songs`, but takes the songs from any iterator without cloning them.
+    pub fn build_from_iter<I: IntoIterator<Item = Song>>(songs: I) -> Self {
         let mut graph = Self::new();
     
-        for song in &songs {
-            graph.add_vertex(song.clone());
+        for song in songs {
+            graph.add_vertex(song);
         }
+        graph.connect_all_pairs();
     
-        for i in 0..songs.len() {
-            for j in 0..songs.len() {
-                if i != j {
-                    graph.add_weighted_edge_by_features(i, j);
+        graph
+    }
+
+    /// Streams a CSV file straight into the graph, keeping only the rows `filter` accepts,
+    /// such as `|song| query.matches(song)` for a `SongQuery`.
+    pub fn build_from_csv(file_path: &str, filter: impl Fn(&Song) -> bool) -> Result<Self, Box<dyn Error>> {
+        let mut graph = Self::new();
+        for result in stream_songs_from_csv(file_path)? {
+            let song = result?;
+            if filter(&song) {
+                graph.add_vertex(song);
+            }
+        }
+        graph.connect_all_pairs();
+
+        Ok(graph)
+    }
+
+    /// Like `build_from_csv`, for songs from any `SongSource`.
+    pub fn build_from_source(source: &dyn SongSource, filter: impl Fn(&Song) -> bool) -> Result<Self, Box<dyn Error>> {
+        Self::build_from_source_observed(source, filter, &Silent)
+    }
+
+    /// `build_from_source` reporting the pairs connected so far to `progress`. If `progress`
+    /// cancels the build, the graph has every song but only the edges added until then.
+    pub fn build_from_source_observed(source: &dyn SongSource, filter: impl Fn(&Song) -> bool, progress: &dyn Progress) -> Result<Self, Box<dyn Error>> {
+        let mut graph = Self::new();
+        for result in source.songs()? {
+            let song = result?;
+            if filter(&song) {
+                graph.add_vertex(song);
+            }
+        }
+        graph.connect_all_pairs_observed(progress);
+
+        Ok(graph)
+    }
+
+    fn connect_all_pairs(&mut self) {
+        self.connect_all_pairs_observed(&Silent);
+    }
+
+    fn connect_all_pairs_observed(&mut self, progress: &dyn Progress) {
+        self.connect_pairs(feature_weight, None, progress);
+    }
+
+    /// Adds an edge weighted by `weight` between every pair of vertices or, with `min_weight`,
+    /// only between the pairs whose weight reaches it. Reports one step per pair considered.
+    pub(crate) fn connect_pairs(&mut self, weight: impl Fn(&Song, &Song) -> Weight, min_weight: Option<Weight>, progress: &dyn Progress) {
+        let vertex_count = self.vertices.len();
+        let mut connected = 0;
+        progress.start("connecting songs", vertex_count * vertex_count.saturating_sub(1) / 2);
+        for i in 0..vertex_count {
+            if progress.is_cancelled() {
+                break;
+            }
+            for j in (i + 1)..vertex_count {
+                let weight = weight(&self.vertices[i], &self.vertices[j]);
+                if min_weight.is_none_or(|min_weight| weight >= min_weight) {
+                    self.add_edge(i, j, weight);
                 }
             }
+            connected += vertex_count - i - 1;
+            progress.advance(connected);
         }
-    
-        graph
+        progress.finish();
     }
 
+    /// Shortest distances from `start_vertex` to every vertex; `f32::MAX` marks unreachable ones.
     pub fn dijkstra(&self, start_vertex: usize) -> Vec<f32> {
         let mut distances = vec![f32::MAX; self.vertices.len()];
         let mut heap = BinaryHeap::new();
@@ -85,38 +169,81 @@ impl Graph {
     
         distances
     }    
-    
-    pub fn closeness_centrality(&self) -> HashMap<usize, (String, String, f32)> {
-        let mut centrality_scores = HashMap::new();
 
-        for (i, song) in self.vertices.iter().enumerate() {
-            let distances = self.dijkstra(i);
-            let sum_distances: f32 = distances.iter().filter(|&&d| d < f32::MAX).sum();
-            let reachable_nodes = distances.iter().filter(|&&d| d < f32::MAX).count() as f32;
-            let closeness = if reachable_nodes > 0.0 { reachable_nodes / sum_distances } else { 0.0 }; 
-            let closeness = closeness * 1000.0;
-            centrality_scores.insert(i, (song.song_name.clone(), song.artist_name.clone(), closeness));
+    /// Shortest path from `from` to `to` as its length and the vertices along it, both ends
+    /// included. `None` when `to` is unreachable.
+    pub fn shortest_path(&self, from: Vertex, to: Vertex) -> Option<(f32, Vec<Vertex>)> {
+        let mut distances = vec![f32::MAX; self.vertices.len()];
+        let mut previous: Vec<Option<Vertex>> = vec![None; self.vertices.len()];
+        let mut heap = BinaryHeap::new();
+
+        distances[from] = 0.0;
+        heap.push(Reverse((OrderedFloat(0.0), from)));
+
+        while let Some(Reverse((OrderedFloat(current_distance), u))) = heap.pop() {
+            if u == to {
+                break;
+            }
+            if current_distance > distances[u] {
+                continue;
+            }
+
+            for &(v, weight) in &self.adjacency_list[u] {
+                let distance = current_distance + weight as f32;
+                if distance < distances[v] {
+                    distances[v] = distance;
+                    previous[v] = Some(u);
+                    heap.push(Reverse((OrderedFloat(distance), v)));
+                }
+            }
         }
-        centrality_scores
+
+        if distances[to] == f32::MAX {
+            return None;
+        }
+        let mut path = vec![to];
+        while let Some(u) = previous[*path.last()?] {
+            path.push(u);
+        }
+        path.reverse();
+        Some((distances[to], path))
+    }
+    
+    /// Closeness of every vertex as reachable vertices (including itself) over the sum of
+    /// finite distances, scaled by 1000, keyed by vertex with its song and artist name.
+    pub fn closeness_centrality(&self) -> CentralityScores {
+        self.centrality(Centrality::Closeness)
     }
     
     
+    /// Prints each vertex's strength followed by the six most central songs.
     pub fn print_most_central_for_depth(&self) {
-        let closeness_scores = self.closeness_centrality();
-        let mut sorted_scores: Vec<_> = closeness_scores.iter().collect();
-        sorted_scores.sort_by(|a, b| b.1 .2.partial_cmp(&a.1 .2).unwrap());
+        self.print_most_central_for_depth_observed(&Silent);
+    }
+
+    /// `print_most_central_for_depth` reporting progress, noting when a cancelled run left the
+    /// ranking partial.
+    pub fn print_most_central_for_depth_observed(&self, progress: &dyn Progress) {
+        let ranking = self.ranking_observed(Centrality::Closeness, progress);
     
-        for (i, adj_list) in self.adjacency_list.iter().enumerate() {
-            println!("Vertex {} has {} edges", i, adj_list.len());
+        for (i, strength) in self.strengths().into_iter().enumerate() {
+            println!("Vertex {} has strength {}", i, strength);
+        }
+        if ranking.len() < self.vertices.len() {
+            println!("Cancelled: only {} of {} songs were scored", ranking.len(), self.vertices.len());
         }
     
-        for (depth, (_, (song_name, artist_name, closeness))) in sorted_scores.iter().enumerate().take(6) {
-            println!("Depth {}: Song: {}, Artist: {}, Closeness: {:.2}", depth + 1, song_name, artist_name, closeness);
+        for entry in ranking.iter().take(6) {
+            println!("Depth {}: Song: {}, Artist: {}, Closeness: {:.2}", entry.competition_rank, entry.song_name, entry.artist_name, entry.score);
         }
     }
 
 
+    /// Whether every vertex is reachable from vertex 0. An empty graph counts as connected.
     pub fn is_connected(&self) -> bool {
+        if self.vertices.is_empty() {
+            return true;
+        }
         let mut visited = vec![false; self.vertices.len()];
         let mut stack = vec![0]; 
     
@@ -133,5 +260,38 @@ impl Graph {
     
         visited.iter().all(|&v| v)
     }
+
+    /// Component label of every vertex, numbered from 0 in order of first vertex.
+    pub fn connected_components(&self) -> Vec<usize> {
+        let mut component = vec![usize::MAX; self.vertices.len()];
+        let mut count = 0;
+
+        for start in 0..self.vertices.len() {
+            if component[start] != usize::MAX {
+                continue;
+            }
+            let mut stack = vec![start];
+            component[start] = count;
+            while let Some(node) = stack.pop() {
+                for &(neighbour, _) in &self.adjacency_list[node] {
+                    if component[neighbour] == usize::MAX {
+                        component[neighbour] = count;
+                        stack.push(neighbour);
+                    }
+                }
+            }
+            count += 1;
+        }
+
+        component
+    }
     
-}
\ No newline at end of file
+}
+
+// Closeness of a vertex from its distance vector, as used by `closeness_centrality`.
+pub(crate) fn closeness(distances: &[f32]) -> f32 {
+    let sum_distances: f32 = distances.iter().filter(|&&d| d < f32::MAX).sum();
+    let reachable_nodes = distances.iter().filter(|&&d| d < f32::MAX).count() as f32;
+    let closeness = if reachable_nodes > 0.0 { reachable_nodes / sum_distances } else { 0.0 };
+    closeness * 1000.0
+}
@@ -0,0 +1,164 @@
+use super::graph::Graph;
+use super::progress::{Progress, Silent};
+use super::song::Song;
+use super::weights::WeightParams;
+use ordered_float::OrderedFloat;
+use std::collections::{BinaryHeap, HashSet};
+
+/// Days on chart, peak position and total streams of a song, scaled by the default
+/// coefficients of the weight formula so that the L1 distance `d` between two songs matches
+/// the weight `1000 / (1 + d)` their edge gets before rounding. Nearest songs are the most
+/// heavily weighted neighbours.
+pub fn features(song: &Song) -> [f64; 3] {
+    features_of(song.days, song.peak_position, song.total_streams)
+}
+
+/// `features` for a song given only its numbers, e.g. one that is not in the dataset.
+pub fn features_of(days: u32, peak_position: u32, total_streams: u64) -> [f64; 3] {
+    scaled_features(days, peak_position, total_streams, &WeightParams::default())
+}
+
+// `features` under any coefficients; the distance then matches `scale / (1 + d)`.
+fn scaled_features(days: u32, peak_position: u32, total_streams: u64, params: &WeightParams) -> [f64; 3] {
+    [
+        params.days as f64 * days as f64 / 10.0,
+        params.peak_position as f64 * peak_position as f64,
+        params.total_streams as f64 * total_streams as f64 / 1_000_000.0,
+    ]
+}
+
+fn song_features(song: &Song, params: &WeightParams) -> [f64; 3] {
+    scaled_features(song.days, song.peak_position, song.total_streams, params)
+}
+
+fn l1(a: &[f64; 3], b: &[f64; 3]) -> f64 {
+    a.iter().zip(b).map(|(x, y)| (x - y).abs()).sum()
+}
+
+/// k-d tree over the `features` of a list of songs, answering exact k nearest neighbour
+/// queries under the L1 distance in O(log n) on typical data instead of a scan of every song.
+pub struct SongIndex {
+    points: Vec<[f64; 3]>,
+    params: WeightParams,
+    // song indices laid out so that the median of every range is that subtree's root
+    tree: Vec<usize>,
+}
+
+impl SongIndex {
+    /// Builds the index in O(n log n). Results refer to songs by their position in `songs`.
+    pub fn new(songs: &[Song]) -> Self {
+        Self::with_params(songs, &WeightParams::default())
+    }
+
+    /// Index whose distances follow the weight formula under `params` instead of the default.
+    pub fn with_params(songs: &[Song], params: &WeightParams) -> Self {
+        let points: Vec<[f64; 3]> = songs.iter().map(|song| song_features(song, params)).collect();
+        let mut tree: Vec<usize> = (0..points.len()).collect();
+        build(&points, &mut tree, 0);
+        SongIndex { points, params: *params, tree }
+    }
+
+    /// Number of songs in the index.
+    pub fn len(&self) -> usize {
+        self.points.len()
+    }
+
+    /// Whether the index holds no songs.
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+
+    /// The `k` songs closest to `point`, nearest first, as `(index, distance)`. Songs at the
+    /// same distance are ordered by index.
+    pub fn nearest(&self, point: &[f64; 3], k: usize) -> Vec<(usize, f64)> {
+        let mut best: BinaryHeap<(OrderedFloat<f64>, usize)> = BinaryHeap::new();
+        if k > 0 {
+            self.search(&self.tree, 0, point, k, &mut best);
+        }
+        let mut nearest: Vec<(usize, f64)> = best.into_iter().map(|(distance, index)| (index, distance.0)).collect();
+        nearest.sort_by(|a, b| a.1.total_cmp(&b.1).then_with(|| a.0.cmp(&b.0)));
+        nearest
+    }
+
+    /// The `k` indexed songs closest to `song`, which need not be indexed itself.
+    pub fn nearest_to_song(&self, song: &Song, k: usize) -> Vec<(usize, f64)> {
+        self.nearest(&song_features(song, &self.params), k)
+    }
+
+    fn search(&self, range: &[usize], depth: usize, point: &[f64; 3], k: usize, best: &mut BinaryHeap<(OrderedFloat<f64>, usize)>) {
+        if range.is_empty() {
+            return;
+        }
+        let mid = range.len() / 2;
+        let index = range[mid];
+        let candidate = (OrderedFloat(l1(point, &self.points[index])), index);
+        if best.len() < k {
+            best.push(candidate);
+        } else if best.peek().is_some_and(|worst| candidate < *worst) {
+            best.pop();
+            best.push(candidate);
+        }
+
+        let axis = depth % 3;
+        let offset = point[axis] - self.points[index][axis];
+        let (near, far) = if offset < 0.0 { (&range[..mid], &range[mid + 1..]) } else { (&range[mid + 1..], &range[..mid]) };
+        self.search(near, depth + 1, point, k, best);
+        // the splitting plane is a lower bound on the L1 distance to anything beyond it
+        if best.len() < k || best.peek().is_some_and(|worst| offset.abs() <= worst.0 .0) {
+            self.search(far, depth + 1, point, k, best);
+        }
+    }
+}
+
+fn build(points: &[[f64; 3]], range: &mut [usize], depth: usize) {
+    if range.len() <= 1 {
+        return;
+    }
+    let axis = depth % 3;
+    let mid = range.len() / 2;
+    range.select_nth_unstable_by(mid, |&a, &b| points[a][axis].total_cmp(&points[b][axis]));
+    let (left, right) = range.split_at_mut(mid);
+    build(points, left, depth + 1);
+    build(points, &mut right[1..], depth + 1);
+}
+
+impl Graph {
+    /// Builds a k nearest neighbour graph: every song is connected to the `k` songs whose
+    /// features are closest, weighted with `feature_weight` like `build_from_songs`, so every
+    /// vertex ends up with at least `k` edges. Uses a `SongIndex` instead of comparing every pair.
+    pub fn build_knn(songs: Vec<Song>, k: usize) -> Self {
+        Self::build_knn_with_weights(songs, k, &WeightParams::default())
+    }
+
+    /// `build_knn` with neighbours chosen and edges weighted by `params`.
+    pub fn build_knn_with_weights(songs: Vec<Song>, k: usize, params: &WeightParams) -> Self {
+        Self::build_knn_with_weights_observed(songs, k, params, &Silent)
+    }
+
+    /// `build_knn_with_weights` reporting one step per song to `progress`. If `progress`
+    /// cancels the build, only the songs linked so far have their nearest neighbour edges.
+    pub fn build_knn_with_weights_observed(songs: Vec<Song>, k: usize, params: &WeightParams, progress: &dyn Progress) -> Self {
+        let index = SongIndex::with_params(&songs, params);
+        let mut graph = Self::new();
+        for song in songs {
+            graph.add_vertex(song);
+        }
+
+        let mut added = HashSet::new();
+        progress.start("linking nearest songs", graph.vertices.len());
+        for u in 0..graph.vertices.len() {
+            if progress.is_cancelled() {
+                break;
+            }
+            for (v, _) in index.nearest_to_song(&graph.vertices[u], k + 1) {
+                if u != v && added.insert((u.min(v), u.max(v))) {
+                    let weight = params.weight(&graph.vertices[u], &graph.vertices[v]);
+                    graph.add_edge(u, v, weight);
+                }
+            }
+            progress.advance(u + 1);
+        }
+        progress.finish();
+        graph
+    }
+}
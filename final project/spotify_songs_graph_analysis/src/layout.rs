@@ -0,0 +1,268 @@
+use super::centrality::Centrality;
+use super::graph::{Graph, Vertex, Weight};
+use super::progress::{Progress, Silent};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use std::collections::BTreeSet;
+use std::error::Error;
+use std::fmt::Write as _;
+use std::fs;
+use std::str::FromStr;
+
+const PALETTE: [&str; 10] = ["#1f77b4", "#ff7f0e", "#2ca02c", "#d62728", "#9467bd", "#8c564b", "#e377c2", "#7f7f7f", "#bcbd22", "#17becf"];
+const MIN_RADIUS: f64 = 3.0;
+const MAX_RADIUS: f64 = 12.0;
+// pull towards the centre that keeps components from drifting to the frame
+const GRAVITY: f64 = 0.05;
+
+/// Settings for `Graph::fruchterman_reingold` and `Graph::write_svg`.
+#[derive(Debug, Clone)]
+pub struct LayoutOptions {
+    pub width: f64,
+    pub height: f64,
+    pub iterations: usize,
+    pub seed: u64,
+    /// Edges lighter than this are left out of the picture, but still pull on the layout.
+    pub min_edge_weight: Weight,
+    /// Only edges among the this many heaviest of at least one endpoint are drawn, so the
+    /// picture has at most `n` times this many lines. A complete graph of every song would
+    /// otherwise draw tens of millions. `usize::MAX` draws every edge.
+    pub edges_per_vertex: usize,
+}
+
+impl Default for LayoutOptions {
+    fn default() -> Self {
+        LayoutOptions { width: 1000.0, height: 1000.0, iterations: 200, seed: 0, min_edge_weight: 0, edges_per_vertex: 10 }
+    }
+}
+
+impl LayoutOptions {
+    /// Checks that the frame has a finite, positive width and height.
+    pub fn validate(&self) -> Result<(), String> {
+        for (name, value) in [("width", self.width), ("height", self.height)] {
+            if !(value.is_finite() && value > 0.0) {
+                return Err(format!("layout {} must be positive and finite, got {}", name, value));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// What the vertices of a rendered graph are coloured by.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Colouring {
+    /// Connected components.
+    Component,
+    /// Communities from `Graph::label_propagation`.
+    Community,
+}
+
+impl FromStr for Colouring {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        match text {
+            "component" => Ok(Colouring::Component),
+            "community" => Ok(Colouring::Community),
+            _ => Err(format!("unknown colouring `{}`, expected component or community", text)),
+        }
+    }
+}
+
+impl Graph {
+    /// Fruchterman-Reingold force directed layout: every pair of vertices repels and every
+    /// edge attracts in proportion to its weight relative to the heaviest edge, so similar
+    /// songs end up close together. A weak pull towards the centre keeps disconnected parts
+    /// in view. The result is scaled to fill the `width` by `height` frame and is the same
+    /// for the same seed. Each iteration is O(n^2 + m). Options that fail
+    /// `LayoutOptions::validate` are an error.
+    pub fn fruchterman_reingold(&self, options: &LayoutOptions) -> Result<Vec<(f64, f64)>, Box<dyn Error>> {
+        self.fruchterman_reingold_observed(options, &Silent)
+    }
+
+    /// `fruchterman_reingold` reporting one step per iteration to `progress`. If `progress`
+    /// cancels, the layout reached so far is scaled to the frame and returned.
+    pub fn fruchterman_reingold_observed(&self, options: &LayoutOptions, progress: &dyn Progress) -> Result<Vec<(f64, f64)>, Box<dyn Error>> {
+        options.validate()?;
+        let n = self.vertices.len();
+        let mut rng = StdRng::seed_from_u64(options.seed);
+        let mut positions: Vec<(f64, f64)> =
+            (0..n).map(|_| (rng.gen_range(0.0..options.width), rng.gen_range(0.0..options.height))).collect();
+        if n < 2 {
+            return Ok(positions);
+        }
+
+        let k = (options.width * options.height / n as f64).sqrt();
+        let max_weight = self.adjacency_list.iter().flatten().map(|&(_, weight)| weight).max().unwrap_or(1).max(1) as f64;
+        let mut temperature = options.width.min(options.height) / 10.0;
+        let cooling = temperature / (options.iterations.max(1) as f64);
+
+        progress.start("laying out songs", options.iterations);
+        for iteration in 0..options.iterations {
+            if progress.is_cancelled() {
+                break;
+            }
+            let mut displacement = vec![(0.0, 0.0); n];
+            for u in 0..n {
+                for v in (u + 1)..n {
+                    let (dx, dy) = (positions[u].0 - positions[v].0, positions[u].1 - positions[v].1);
+                    let distance = (dx * dx + dy * dy).sqrt().max(0.01);
+                    let force = k * k / distance;
+                    displacement[u].0 += dx / distance * force;
+                    displacement[u].1 += dy / distance * force;
+                    displacement[v].0 -= dx / distance * force;
+                    displacement[v].1 -= dy / distance * force;
+                }
+            }
+            for (u, adj_list) in self.adjacency_list.iter().enumerate() {
+                for &(v, weight) in adj_list.iter().filter(|&&(v, _)| u < v) {
+                    let (dx, dy) = (positions[u].0 - positions[v].0, positions[u].1 - positions[v].1);
+                    let distance = (dx * dx + dy * dy).sqrt().max(0.01);
+                    let force = distance * distance / k * (weight.max(0) as f64 / max_weight);
+                    displacement[u].0 -= dx / distance * force;
+                    displacement[u].1 -= dy / distance * force;
+                    displacement[v].0 += dx / distance * force;
+                    displacement[v].1 += dy / distance * force;
+                }
+            }
+            for (position, (dx, dy)) in positions.iter().zip(displacement.iter_mut()) {
+                let (cx, cy) = (position.0 - options.width / 2.0, position.1 - options.height / 2.0);
+                let distance = (cx * cx + cy * cy).sqrt();
+                *dx -= cx * distance / k * GRAVITY;
+                *dy -= cy * distance / k * GRAVITY;
+            }
+
+            for (position, (dx, dy)) in positions.iter_mut().zip(displacement) {
+                let length = (dx * dx + dy * dy).sqrt();
+                if length > 0.0 {
+                    let step = length.min(temperature);
+                    position.0 += dx / length * step;
+                    position.1 += dy / length * step;
+                }
+            }
+            temperature = (temperature - cooling).max(0.0);
+            progress.advance(iteration + 1);
+        }
+        progress.finish();
+
+        fit_to_frame(&mut positions, options.width, options.height);
+        Ok(positions)
+    }
+
+    /// Lays the graph out and writes it as a standalone SVG. Vertices are sized by closeness,
+    /// with vertices whose closeness is not finite drawn smallest, and coloured by
+    /// `colouring`; hovering a vertex shows its song and artist. Edges are thinned out as
+    /// `min_edge_weight` and `edges_per_vertex` say.
+    pub fn write_svg(&self, file_path: &str, options: &LayoutOptions, colouring: Colouring) -> Result<(), Box<dyn Error>> {
+        self.write_svg_observed(file_path, options, colouring, &Silent)
+    }
+
+    /// `write_svg` reporting the layout and the closeness run to `progress`. If `progress`
+    /// cancels either, nothing is written and the result is an error.
+    pub fn write_svg_observed(&self, file_path: &str, options: &LayoutOptions, colouring: Colouring, progress: &dyn Progress) -> Result<(), Box<dyn Error>> {
+        let positions = self.fruchterman_reingold_observed(options, progress)?;
+        let groups = match colouring {
+            Colouring::Component => self.connected_components(),
+            Colouring::Community => self.label_propagation(options.seed),
+        };
+
+        let closeness = self.centrality_observed(Centrality::Closeness, progress);
+        if progress.is_cancelled() {
+            return Err("cancelled before the SVG was written".into());
+        }
+        let scores: Vec<f32> = (0..self.vertices.len()).map(|v| closeness.get(&v).map_or(f32::NAN, |entry| entry.2)).collect();
+        let finite = scores.iter().copied().filter(|score| score.is_finite());
+        let (low, high) = finite.fold((f32::MAX, f32::MIN), |(low, high), score| (low.min(score), high.max(score)));
+        let radius = |score: f32| {
+            if !score.is_finite() || high <= low {
+                MIN_RADIUS
+            } else {
+                MIN_RADIUS + (MAX_RADIUS - MIN_RADIUS) * ((score - low) / (high - low)) as f64
+            }
+        };
+
+        let margin = MAX_RADIUS * 2.0;
+        let mut svg = String::new();
+        writeln!(
+            svg,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="{x} {x} {w} {h}">"#,
+            w = options.width + 2.0 * margin,
+            h = options.height + 2.0 * margin,
+            x = -margin
+        )?;
+        writeln!(svg, r##"<rect x="{x}" y="{x}" width="100%" height="100%" fill="#ffffff"/>"##, x = -margin)?;
+
+        let max_weight = self.adjacency_list.iter().flatten().map(|&(_, weight)| weight).max().unwrap_or(1).max(1) as f64;
+        writeln!(svg, r##"<g stroke="#999999">"##)?;
+        for (u, v, weight) in self.drawn_edges(options) {
+            writeln!(
+                svg,
+                r#"<line x1="{:.1}" y1="{:.1}" x2="{:.1}" y2="{:.1}" stroke-opacity="{:.2}"/>"#,
+                positions[u].0,
+                positions[u].1,
+                positions[v].0,
+                positions[v].1,
+                (0.05 + 0.5 * weight.max(0) as f64 / max_weight).min(1.0)
+            )?;
+        }
+        writeln!(svg, "</g>")?;
+
+        writeln!(svg, r##"<g stroke="#ffffff" stroke-width="0.5">"##)?;
+        for (v, song) in self.vertices.iter().enumerate() {
+            writeln!(
+                svg,
+                r#"<circle cx="{:.1}" cy="{:.1}" r="{:.1}" fill="{}"><title>{} - {}</title></circle>"#,
+                positions[v].0,
+                positions[v].1,
+                radius(scores[v]),
+                PALETTE[groups[v] % PALETTE.len()],
+                escape(&song.song_name),
+                escape(&song.artist_name)
+            )?;
+        }
+        writeln!(svg, "</g>")?;
+        writeln!(svg, "</svg>")?;
+
+        fs::write(file_path, svg)?;
+        Ok(())
+    }
+
+    // Edges `write_svg` draws as `(u, v, weight)` with `u < v`, in order: those at least
+    // `min_edge_weight` heavy among the `edges_per_vertex` heaviest of either endpoint, ties
+    // going to the lower vertex.
+    fn drawn_edges(&self, options: &LayoutOptions) -> BTreeSet<(Vertex, Vertex, Weight)> {
+        let mut edges = BTreeSet::new();
+        for (u, adj_list) in self.adjacency_list.iter().enumerate() {
+            let mut heaviest: Vec<(Vertex, Weight)> = adj_list.iter().copied().filter(|&(_, weight)| weight >= options.min_edge_weight).collect();
+            let order = |a: &(Vertex, Weight), b: &(Vertex, Weight)| b.1.cmp(&a.1).then(a.0.cmp(&b.0));
+            if heaviest.len() > options.edges_per_vertex {
+                if options.edges_per_vertex > 0 {
+                    heaviest.select_nth_unstable_by(options.edges_per_vertex - 1, order);
+                }
+                heaviest.truncate(options.edges_per_vertex);
+            }
+            for (v, weight) in heaviest {
+                edges.insert((u.min(v), u.max(v), weight));
+            }
+        }
+        edges
+    }
+}
+
+// Scales and moves the positions to fill the frame, keeping their proportions.
+fn fit_to_frame(positions: &mut [(f64, f64)], width: f64, height: f64) {
+    let (mut min_x, mut min_y, mut max_x, mut max_y) = (f64::MAX, f64::MAX, f64::MIN, f64::MIN);
+    for &(x, y) in positions.iter() {
+        (min_x, min_y, max_x, max_y) = (min_x.min(x), min_y.min(y), max_x.max(x), max_y.max(y));
+    }
+    let scale = (width / (max_x - min_x)).min(height / (max_y - min_y));
+    let scale = if scale.is_finite() { scale } else { 1.0 };
+    let (offset_x, offset_y) = ((width - (max_x - min_x) * scale) / 2.0, (height - (max_y - min_y) * scale) / 2.0);
+    for position in positions.iter_mut() {
+        position.0 = (position.0 - min_x) * scale + offset_x;
+        position.1 = (position.1 - min_y) * scale + offset_y;
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
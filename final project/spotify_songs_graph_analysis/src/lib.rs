@@ -0,0 +1,70 @@
+//! Graph analysis of the Spotify chart dataset.
+//!
+//! Songs are loaded with [`song::load_songs_from_csv`] and connected into a weighted
+//! [`Graph`] with [`Graph::build_from_songs`], where each edge weight reflects how similar
+//! two songs' chart runs are. The graph then supports shortest paths, closeness
+//! centrality, clustering and summary statistics, and can be collapsed into an artist graph.
+//!
+//! ```no_run
+//! use spotify_songs_graph_analysis::{load_songs_from_csv, Graph};
+//! use spotify_songs_graph_analysis::sample::uniform_subset;
+//!
+//! let songs = load_songs_from_csv("Spotify_final_dataset.csv").unwrap();
+//! let graph = Graph::build_from_songs(uniform_subset(&songs, 500, 0));
+//! graph.print_most_central_for_depth();
+//! println!("{}", graph.stats());
+//! ```
+
+/// Song records and CSV loading.
+pub mod song;
+/// Reading songs from CSV/TSV with custom columns, JSON and a binary columnar format.
+pub mod source;
+/// Selecting the songs a graph is built over.
+pub mod query;
+/// The song graph, shortest paths, closeness centrality and connectivity.
+pub mod graph;
+/// Harmonic and Wasserman-Faust closeness alongside the original closeness.
+pub mod centrality;
+/// Ordered centrality rankings with stable tie-breaks and rank numbers.
+pub mod ranking;
+/// Progress observers and cooperative cancellation for long running algorithms.
+pub mod progress;
+/// Checkpointing and resuming all-sources centrality runs.
+pub mod checkpoint;
+/// Rank correlations between centrality measures and streaming numbers.
+pub mod compare;
+/// Collapsing the song graph into one vertex per artist.
+pub mod artist;
+/// k-d tree over song features and k nearest neighbour graphs.
+pub mod index;
+/// Edge weight formula, weight distribution diagnostics and pruning thresholds.
+pub mod weights;
+/// Summary statistics of a graph.
+pub mod stats;
+/// Vertex strength and ego network extraction.
+pub mod ego;
+/// Triangle counting and clustering coefficients.
+pub mod clustering;
+/// Community detection by label propagation.
+pub mod community;
+/// Force directed layout and SVG rendering.
+pub mod layout;
+/// Spectral and random walk node embeddings.
+pub mod embedding;
+/// Differences between two graphs built from different data.
+pub mod diff;
+/// Reproducible subsets of the song list.
+pub mod sample;
+/// Dated chart snapshots, per-period graphs and closeness over time.
+pub mod temporal;
+/// Local HTTP server answering JSON queries about a graph.
+pub mod server;
+/// Interactive shell for exploring a graph.
+pub mod repl;
+/// TOML files describing a whole analysis run.
+pub mod config;
+/// Seeded generators for graphs of known structure.
+pub mod generators;
+
+pub use graph::{Graph, Vertex, Weight};
+pub use song::{load_songs_from_csv, Song};
@@ -1,62 +1,343 @@
-mod song;
-mod graph;
+use std::env;
+use std::ops::Bound;
+use spotify_songs_graph_analysis::{Graph, Song, Weight};
+use spotify_songs_graph_analysis::query::SongQuery;
+use spotify_songs_graph_analysis::source::{format_for_path, source_for_format, source_for_path, Column, ColumnMapping, SongSource};
+use spotify_songs_graph_analysis::temporal::{load_chart_entries_from_csv, CentralityTimeline, Period};
+use spotify_songs_graph_analysis::artist::WeightAggregation;
+use spotify_songs_graph_analysis::centrality::Centrality;
+use spotify_songs_graph_analysis::config::RunConfig;
+use spotify_songs_graph_analysis::embedding::{DeepWalkOptions, EmbeddingMethod};
+use spotify_songs_graph_analysis::index::{features_of, SongIndex};
+use spotify_songs_graph_analysis::layout::{Colouring, LayoutOptions};
+use spotify_songs_graph_analysis::progress::{ConsoleProgress, Progress};
+use spotify_songs_graph_analysis::ranking::rank;
+use spotify_songs_graph_analysis::repl::Repl;
+use spotify_songs_graph_analysis::server::GraphServer;
+use spotify_songs_graph_analysis::sample::{stratified_subset, uniform_subset};
+use spotify_songs_graph_analysis::weights::{connectivity_threshold, weight_distribution, WeightParams};
 
-use crate::song::{load_songs_from_csv, Song};
-use crate::graph::Graph;
 
 
+fn arg_value(name: &str) -> Option<String> {
+    let prefix = format!("--{}=", name);
+    env::args().find_map(|arg| arg.strip_prefix(&prefix).map(str::to_string))
+}
 
-fn main() {
-    let songs = load_songs_from_csv("Spotify_final_dataset.csv").expect("Failed to load songs");
+fn arg_values(name: &str) -> Vec<String> {
+    let prefix = format!("--{}=", name);
+    env::args().filter_map(|arg| arg.strip_prefix(&prefix).map(str::to_string)).collect()
+}
+
+fn parsed_arg<T: std::str::FromStr>(name: &str) -> Option<T> {
+    arg_value(name).map(|value| value.parse().unwrap_or_else(|_| panic!("Invalid value for --{}: {}", name, value)))
+}
+
+fn range<T: Copy>(min: Option<T>, max: Option<T>) -> (Bound<T>, Bound<T>) {
+    (min.map_or(Bound::Unbounded, Bound::Included), max.map_or(Bound::Unbounded, Bound::Included))
+}
+
+fn query_from_args() -> SongQuery {
+    let mut query = SongQuery::new()
+        .days(range::<u32>(parsed_arg("min-days"), parsed_arg("max-days")))
+        .peak_position(range::<u32>(parsed_arg("min-peak"), parsed_arg("max-peak")))
+        .total_streams(range::<u64>(parsed_arg("min-streams"), parsed_arg("max-streams")))
+        .exclude_artists(arg_values("exclude-artist"));
+
+    let included = arg_values("include-artist");
+    if !included.is_empty() {
+        query = query.include_artists(included);
+    }
+    if let Some(text) = arg_value("artist-contains") {
+        query = query.artist_name_contains(&text);
+    }
+    if let Some(text) = arg_value("song-contains") {
+        query = query.song_name_contains(&text);
+    }
+    if let Some(n) = parsed_arg("top") {
+        query = query.top_by_streams(n);
+    }
+    query
+}
+
+const DATASET: &str = "Spotify_final_dataset.csv";
+
+fn source_from_args() -> Box<dyn SongSource> {
+    let path = arg_value("input").unwrap_or_else(|| DATASET.to_string());
+    let format = arg_value("format").unwrap_or_else(|| format_for_path(&path).to_string());
+
+    let mut mapping = ColumnMapping::default();
+    for column in arg_values("column") {
+        let (field, name) = column.split_once('=').unwrap_or_else(|| panic!("Expected --column=field=column, got {}", column));
+        let column = match name.strip_prefix('#') {
+            Some(index) => Column::Index(index.parse().unwrap_or_else(|_| panic!("Invalid column index: {}", name))),
+            None => Column::Name(name.to_string()),
+        };
+        mapping.set(field, column).unwrap_or_else(|e| panic!("Invalid --column: {}", e));
+    }
+
+    source_for_format(&path, &format, mapping).unwrap_or_else(|e| panic!("{}", e))
+}
+
+fn print_timeline(path: &str) {
+    let entries = load_chart_entries_from_csv(path).expect("Failed to load chart snapshots");
+    let period: Period = arg_value("period").map_or(Period::Week, |text| text.parse().unwrap_or_else(|e| panic!("{}", e)));
+    let timeline = CentralityTimeline::build(&entries, period).expect("Failed to build timeline");
+
+    println!("{} periods from {} to {}", timeline.periods.len(), timeline.periods.first().map_or("-", |p| p), timeline.periods.last().map_or("-", |p| p));
+    for (title, songs) in [("Rising", timeline.rising(10)), ("Falling", timeline.falling(10))] {
+        println!("{}:", title);
+        for song in songs {
+            println!("  Song: {}, Artist: {}, Change: {:+.2}", song.song_name, song.artist_name, song.net_change());
+        }
+    }
+
+    if let Some(output) = arg_value("timeline-csv") {
+        timeline.write_csv(&output).expect("Failed to write timeline");
+    }
+}
+
+fn selected_songs(source: &dyn SongSource, query: &SongQuery, sample_size: Option<usize>) -> Vec<Song> {
+    let songs: Vec<Song> = source
+        .songs()
+        .expect("Failed to load songs")
+        .map(|result| result.expect("Failed to load songs"))
+        .filter(|song| query.matches(song))
+        .collect();
+    let songs = query.apply(songs);
+    match sample_size {
+        Some(size) => {
+            let seed = parsed_arg("seed").unwrap_or(0);
+            match parsed_arg::<usize>("strata") {
+                Some(strata) => stratified_subset(&songs, size, strata, seed),
+                None => uniform_subset(&songs, size, seed),
+            }
+        }
+        None => songs,
+    }
+}
+
+fn print_weight_diagnostics(songs: &[Song], degree: f64) {
+    let distribution = weight_distribution(songs, 1_000_000, 20, parsed_arg("seed").unwrap_or(0));
+    print!("{}", distribution);
+    println!("Keep weights >= {} for an average degree of about {}", distribution.threshold_for_average_degree(degree), degree);
+    if let Some(threshold) = connectivity_threshold(songs) {
+        println!("Keep weights >= {} to stay connected", threshold);
+    }
+}
+
+fn print_nearest(songs: &[Song], numbers: &str) {
+    let values: Vec<u64> = numbers.split(',').map(|value| value.trim().parse().unwrap_or_else(|_| panic!("Invalid value for --nearest: {}", numbers))).collect();
+    let [days, peak_position, total_streams] = values[..] else {
+        panic!("--nearest expects days,peak_position,total_streams");
+    };
+    let index = SongIndex::new(songs);
+    for (i, distance) in index.nearest(&features_of(days as u32, peak_position as u32, total_streams), 10) {
+        println!("Song: {}, Artist: {}, Weight: {:.0}", songs[i].song_name, songs[i].artist_name, 1000.0 / (1.0 + distance));
+    }
+}
 
-    let graph = Graph::build_from_songs(songs);
+// Prints the songs around the vertex numbered or named `name`, strongest within the ego network first.
+// --hops sets the radius and --ego-min-weight drops edges lighter than it.
+fn print_ego_network(graph: &Graph, name: &str) {
+    let center = name
+        .parse::<usize>()
+        .ok()
+        .filter(|&vertex| vertex < graph.vertices.len())
+        .or_else(|| graph.vertices.iter().position(|song| song.song_name.trim().eq_ignore_ascii_case(name.trim())))
+        .unwrap_or_else(|| panic!("No song numbered or named {}", name));
+    let hops = parsed_arg("hops").unwrap_or(1);
+    let ego = graph.ego_network(center, hops, parsed_arg::<Weight>("ego-min-weight"));
 
-    graph.print_most_central_for_depth();
+    let edges: usize = ego.adjacency_list.iter().map(Vec::len).sum::<usize>() / 2;
+    println!("Song: {}, Artist: {}", ego.vertices[0].song_name, ego.vertices[0].artist_name);
+    println!("{} songs and {} edges within {} hops", ego.vertices.len(), edges, hops);
+    let strengths = ego.strengths();
+    let mut members: Vec<usize> = (1..ego.vertices.len()).collect();
+    members.sort_by_key(|&v| std::cmp::Reverse(strengths[v]));
+    for v in members.into_iter().take(10) {
+        println!("  Song: {}, Artist: {}, Strength: {}", ego.vertices[v].song_name, ego.vertices[v].artist_name, strengths[v]);
+    }
+}
+
+// Builds the complete graph, a thresholded one with --min-weight or a k-NN one with --knn.
+fn build_graph(source: &dyn SongSource, query: &SongQuery, sample_size: Option<usize>, progress: &dyn Progress) -> Graph {
+    let min_weight = parsed_arg::<Weight>("min-weight");
+    let knn = parsed_arg::<usize>("knn");
+    let graph = if sample_size.is_none() && min_weight.is_none() && knn.is_none() && query.is_streamable() {
+        Graph::build_from_source_observed(source, |song| query.matches(song), progress).expect("Failed to load songs")
+    } else {
+        let songs = selected_songs(source, query, sample_size);
+        match knn {
+            Some(k) => Graph::build_knn_with_weights_observed(songs, k, &WeightParams::default(), progress),
+            None => Graph::build_with_weights_observed(songs, &WeightParams::default(), min_weight, progress),
+        }
+    };
+    if progress.is_cancelled() {
+        eprintln!("Cancelled while connecting songs");
+        std::process::exit(130);
+    }
+    graph
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use rand::{thread_rng, seq::SliceRandom};
+fn main() {
+    if let Some(path) = arg_value("config") {
+        let config = RunConfig::from_file(&path).unwrap_or_else(|e| panic!("Invalid config {}: {}", path, e));
+        let progress = ConsoleProgress::with_ctrlc().expect("Failed to install Ctrl-C handler");
+        if let Err(e) = config.run_observed(&mut std::io::stdout(), progress.as_ref()) {
+            eprintln!("Run failed: {}", e);
+            std::process::exit(if progress.is_cancelled() { 130 } else { 1 });
+        }
+        return;
+    }
+
+    if let Some(path) = arg_value("timeline") {
+        print_timeline(&path);
+        return;
+    }
 
-    fn get_random_subset(file_path: &str, subset_size: usize) -> Vec<Song> {
-        let songs = load_songs_from_csv(file_path).unwrap();
-        let mut rng = thread_rng();
-        let mut shuffled_songs = songs.clone();
-        shuffled_songs.shuffle(&mut rng);
-        shuffled_songs.into_iter().take(subset_size).collect()
+    let query = query_from_args();
+    let sample_size = parsed_arg::<usize>("sample");
+    let source = source_from_args();
+
+    if let Some(flag) = env::args().find(|arg| arg.starts_with("--weights")) {
+        let degree: f64 = flag.strip_prefix("--weights=").map_or(10.0, |value| value.parse().unwrap_or_else(|_| panic!("Invalid value for --weights: {}", value)));
+        print_weight_diagnostics(&selected_songs(source.as_ref(), &query, sample_size), degree);
+        return;
+    }
+
+    if let Some(numbers) = arg_value("nearest") {
+        print_nearest(&selected_songs(source.as_ref(), &query, sample_size), &numbers);
+        return;
     }
 
-    #[test]
-    fn test_graph_construction_with_random_subset() {
-        let song_subset = get_random_subset("Spotify_final_dataset.csv", 1000);
-        let graph = Graph::build_from_songs(song_subset);
-        assert!(!graph.vertices.is_empty());
-        assert_eq!(graph.vertices.len(), 1000);
-        assert_ne!(graph.adjacency_list[0].len(), 0);
+    let progress = ConsoleProgress::with_ctrlc().expect("Failed to install Ctrl-C handler");
+    let graph = build_graph(source.as_ref(), &query, sample_size, progress.as_ref());
+    if graph.vertices.is_empty() {
+        eprintln!("No songs match the filters");
+        std::process::exit(1);
+    }
+    if !graph.is_connected() {
+        eprintln!("Warning: graph is not connected, closeness only covers reachable songs");
     }
 
-    #[test]
-    fn test_dijkstra_algorithm() {
-        let song_subset = get_random_subset("Spotify_final_dataset.csv", 1000);
-        let graph = Graph::build_from_songs(song_subset);
-        let distances = graph.dijkstra(0);
-        assert!(distances[1] > 0.0);
-        assert_ne!(distances[1], f32::MAX);
+    if let Some(path) = arg_value("diff") {
+        let new = build_graph(source_for_path(&path).as_ref(), &query, sample_size, progress.as_ref());
+        let metric = arg_value("metric").map(|name| name.parse::<Centrality>().unwrap_or_else(|e| panic!("{}", e)));
+        print!("{}", graph.diff_observed(&new, parsed_arg("tolerance").unwrap_or(0), metric, progress.as_ref()));
+        if progress.is_cancelled() {
+            println!("Cancelled: rank moves only cover songs scored in both graphs");
+        }
+        return;
     }
 
-    #[test]
-    fn test_closeness_centrality() {
-        let song_subset = get_random_subset("Spotify_final_dataset.csv", 1000);
-        let graph = Graph::build_from_songs(song_subset);
-        let centrality_scores = graph.closeness_centrality();
-        assert!(centrality_scores.iter().any(|(_, &(_, _, closeness))| closeness > 0.0));
+    if let Some(method) = arg_value("embed") {
+        let dimensions = parsed_arg("dimensions").unwrap_or(16);
+        let seed = parsed_arg("seed").unwrap_or(0);
+        let embedding = match method.parse::<EmbeddingMethod>().unwrap_or_else(|e| panic!("{}", e)) {
+            EmbeddingMethod::Spectral => graph.spectral_embedding(dimensions, parsed_arg("iterations").unwrap_or(300), seed),
+            EmbeddingMethod::DeepWalk => graph.deepwalk(&DeepWalkOptions { dimensions, seed, ..DeepWalkOptions::default() }),
+        };
+        let output = arg_value("embed-csv").unwrap_or_else(|| format!("{}_embedding.csv", method));
+        embedding.write_csv(&graph, &output).expect("Failed to write embedding");
+        println!("Wrote {} {}-dimensional vectors to {}", embedding.vectors.len(), embedding.dimensions(), output);
+        return;
     }
 
-    #[test]
-    fn test_graph_connectivity() {
-        let song_subset = get_random_subset("Spotify_final_dataset.csv", 1000);
-        let graph = Graph::build_from_songs(song_subset);
-        assert!(graph.is_connected());
+    if let Some(output) = arg_value("svg") {
+        let defaults = LayoutOptions::default();
+        let options = LayoutOptions {
+            iterations: parsed_arg("iterations").unwrap_or(defaults.iterations),
+            seed: parsed_arg("seed").unwrap_or(defaults.seed),
+            min_edge_weight: parsed_arg("min-edge-weight").unwrap_or(defaults.min_edge_weight),
+            edges_per_vertex: parsed_arg("edges-per-vertex").unwrap_or(defaults.edges_per_vertex),
+            ..defaults
+        };
+        let colouring: Colouring = arg_value("colour").map_or(Colouring::Component, |text| text.parse().unwrap_or_else(|e| panic!("{}", e)));
+        if let Err(e) = graph.write_svg_observed(&output, &options, colouring, progress.as_ref()) {
+            eprintln!("Failed to write SVG: {}", e);
+            std::process::exit(if progress.is_cancelled() { 130 } else { 1 });
+        }
+        return;
+    }
+
+    if let Some(name) = arg_value("ego") {
+        print_ego_network(&graph, &name);
+        return;
+    }
+
+    if env::args().skip(1).any(|arg| arg == "repl") {
+        Repl::new(graph).run().expect("Shell failed");
+        return;
+    }
+
+    if env::args().skip(1).any(|arg| arg == "serve") {
+        let address = arg_value("addr").unwrap_or_else(|| "127.0.0.1:8080".to_string());
+        println!("Serving {} songs on http://{}", graph.vertices.len(), address);
+        let mut server = GraphServer::new(graph);
+        if let Some(origin) = arg_value("allow-origin") {
+            server = server.with_allow_origin(origin);
+        }
+        server.serve(address.as_str()).expect("Failed to serve");
+        return;
+    }
+
+    if let Some(format) = env::args().find(|arg| arg.starts_with("--stats")) {
+        let stats = graph.stats_observed(progress.as_ref());
+        if progress.is_cancelled() {
+            eprintln!("Cancelled while counting triangles");
+            std::process::exit(130);
+        }
+        if format == "--stats=json" {
+            println!("{}", stats.to_json().expect("Failed to serialise stats"));
+        } else {
+            print!("{}", stats);
+        }
+        return;
+    }
+
+    if let Some(flag) = env::args().find(|arg| arg.starts_with("--compare") && !arg.starts_with("--compare-csv")) {
+        let metrics: Vec<Centrality> = match flag.strip_prefix("--compare=") {
+            Some(list) => list.split(',').map(|name| name.parse().unwrap_or_else(|e| panic!("{}", e))).collect(),
+            None => Centrality::ALL.to_vec(),
+        };
+        let comparison = graph.compare_centrality_observed(&metrics, progress.as_ref());
+        if progress.is_cancelled() {
+            eprintln!("Cancelled while comparing measures");
+            std::process::exit(130);
+        }
+        print!("{}", comparison);
+        if let Some(output) = arg_value("compare-csv") {
+            comparison.write_csv(&output).expect("Failed to write comparison");
+        }
+        return;
+    }
+
+    if let Some(path) = arg_value("checkpoint") {
+        let metric = arg_value("metric").map_or(Centrality::Closeness, |name| name.parse().unwrap_or_else(|e| panic!("{}", e)));
+        let interval = parsed_arg("checkpoint-every").unwrap_or(100);
+        let scores = graph.centrality_checkpointed(metric, &path, interval, progress.as_ref()).unwrap_or_else(|e| panic!("{}", e));
+        if scores.len() < graph.vertices.len() {
+            println!("Cancelled: only {} of {} songs were scored, run again to resume from {}", scores.len(), graph.vertices.len(), path);
+        }
+        for entry in rank(&graph, &scores).iter().take(6) {
+            println!("Depth {}: Song: {}, Artist: {}, {}: {:.4}", entry.competition_rank, entry.song_name, entry.artist_name, metric, entry.score);
+        }
+        return;
+    }
+
+    let aggregation = env::args().find_map(|arg| match arg.as_str() {
+        "--artists" | "--artists=mean" => Some(WeightAggregation::Mean),
+        "--artists=max" => Some(WeightAggregation::Max),
+        "--artists=sum" => Some(WeightAggregation::Sum),
+        _ => None,
+    });
+
+    match aggregation {
+        Some(aggregation) => graph.build_artist_graph(aggregation).print_most_central_artists_observed(progress.as_ref()),
+        None => match arg_value("metric") {
+            Some(metric) => graph.print_most_central_observed(metric.parse::<Centrality>().unwrap_or_else(|e| panic!("{}", e)), progress.as_ref()),
+            None => graph.print_most_central_for_depth_observed(progress.as_ref()),
+        },
     }
 }
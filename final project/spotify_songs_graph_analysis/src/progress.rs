@@ -0,0 +1,149 @@
+use std::io::Write;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Observer of a long running operation made of countable steps, such as one Dijkstra run
+/// per source vertex. Every method has a default that does nothing, so observers only
+/// implement what they need.
+pub trait Progress {
+    /// Called once before the first step with the number of steps the operation takes.
+    fn start(&self, _task: &str, _total: usize) {}
+
+    /// Called after each step with the number of steps done so far.
+    fn advance(&self, _done: usize) {}
+
+    /// Called once when the operation stops, whether it finished or was cancelled.
+    fn finish(&self) {}
+
+    /// Polled before every step. Once it returns `true` the operation stops early and returns
+    /// what it has computed so far.
+    fn is_cancelled(&self) -> bool {
+        false
+    }
+}
+
+/// Observer that ignores everything, used by the methods without a progress argument.
+pub struct Silent;
+
+impl Progress for Silent {}
+
+/// Flag that cancels any operation observing it once set, from another thread or a signal
+/// handler. Clones share the flag.
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    /// Token that is not cancelled yet.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the flag. Operations notice it the next time they poll `is_cancelled`.
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+
+    /// Whether `cancel` has been called on this token or any of its clones.
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::SeqCst)
+    }
+}
+
+impl Progress for CancelToken {
+    fn is_cancelled(&self) -> bool {
+        CancelToken::is_cancelled(self)
+    }
+}
+
+/// Estimated time left after `done` of `total` steps took `elapsed`, assuming the remaining
+/// steps take as long on average. `None` before the first step.
+pub fn eta(elapsed: Duration, done: usize, total: usize) -> Option<Duration> {
+    if done == 0 {
+        return None;
+    }
+    Some(elapsed.mul_f64(total.saturating_sub(done) as f64 / done as f64))
+}
+
+/// Formats a duration as `1h02m`, `3m05s` or `12s`.
+pub fn format_duration(duration: Duration) -> String {
+    let seconds = duration.as_secs();
+    match (seconds / 3600, seconds / 60 % 60, seconds % 60) {
+        (0, 0, s) => format!("{}s", s),
+        (0, m, s) => format!("{}m{:02}s", m, s),
+        (h, m, _) => format!("{}h{:02}m", h, m),
+    }
+}
+
+struct ConsoleState {
+    task: String,
+    total: usize,
+    started: Instant,
+    last_drawn: Option<Instant>,
+}
+
+/// Progress line on stderr with the percentage done, elapsed time and ETA, redrawn at most
+/// five times a second. Cancelled through its `CancelToken`.
+pub struct ConsoleProgress {
+    token: CancelToken,
+    running: AtomicBool,
+    state: Mutex<ConsoleState>,
+}
+
+impl ConsoleProgress {
+    /// Console progress cancelled through `token`, with no Ctrl-C handler installed.
+    pub fn new(token: CancelToken) -> Self {
+        let state = ConsoleState { task: String::new(), total: 0, started: Instant::now(), last_drawn: None };
+        ConsoleProgress { token, running: AtomicBool::new(false), state: Mutex::new(state) }
+    }
+
+    /// Console progress cancelled by Ctrl-C. While an operation is running, the first Ctrl-C
+    /// cancels it so the caller can use its partial result and a second one exits. Between
+    /// operations Ctrl-C exits straight away, as it would without a handler.
+    pub fn with_ctrlc() -> Result<Arc<Self>, ctrlc::Error> {
+        let progress = Arc::new(Self::new(CancelToken::new()));
+        let handler_progress = Arc::clone(&progress);
+        ctrlc::set_handler(move || {
+            if !handler_progress.running.load(Ordering::SeqCst) || handler_progress.token.is_cancelled() {
+                std::process::exit(130);
+            }
+            handler_progress.token.cancel();
+        })?;
+        Ok(progress)
+    }
+}
+
+impl Progress for ConsoleProgress {
+    fn start(&self, task: &str, total: usize) {
+        self.running.store(true, Ordering::SeqCst);
+        let mut state = self.state.lock().unwrap();
+        *state = ConsoleState { task: task.to_string(), total, started: Instant::now(), last_drawn: None };
+    }
+
+    fn advance(&self, done: usize) {
+        let mut state = self.state.lock().unwrap();
+        let now = Instant::now();
+        if state.last_drawn.is_some_and(|last| now - last < Duration::from_millis(200)) && done < state.total {
+            return;
+        }
+        state.last_drawn = Some(now);
+
+        let elapsed = now - state.started;
+        let percent = if state.total == 0 { 100.0 } else { 100.0 * done as f64 / state.total as f64 };
+        let eta = eta(elapsed, done, state.total).map_or("-".to_string(), format_duration);
+        eprint!("\r{}: {}/{} ({:.1}%), elapsed {}, ETA {}   ", state.task, done, state.total, percent, format_duration(elapsed), eta);
+        let _ = std::io::stderr().flush();
+    }
+
+    fn finish(&self) {
+        self.running.store(false, Ordering::SeqCst);
+        let state = self.state.lock().unwrap();
+        if state.last_drawn.is_some() {
+            eprintln!();
+        }
+    }
+
+    fn is_cancelled(&self) -> bool {
+        self.token.is_cancelled()
+    }
+}
@@ -0,0 +1,139 @@
+use super::song::Song;
+use std::collections::HashSet;
+use std::ops::{Bound, RangeBounds};
+
+/// Selects the songs a graph is built over. Every condition set on the query must hold;
+/// `top_by_streams` is applied last, to the songs that pass the other conditions.
+///
+/// ```
+/// use spotify_songs_graph_analysis::query::SongQuery;
+///
+/// let query = SongQuery::new().peak_position(..=10).exclude_artists(["Drake"]).top_by_streams(100);
+/// ```
+#[derive(Debug, Clone)]
+pub struct SongQuery {
+    days: (Bound<u32>, Bound<u32>),
+    peak_position: (Bound<u32>, Bound<u32>),
+    total_streams: (Bound<u64>, Bound<u64>),
+    artist_name_contains: Option<String>,
+    song_name_contains: Option<String>,
+    include_artists: Option<HashSet<String>>,
+    exclude_artists: HashSet<String>,
+    top_by_streams: Option<usize>,
+}
+
+impl Default for SongQuery {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SongQuery {
+    /// A query that keeps every song.
+    pub fn new() -> Self {
+        SongQuery {
+            days: (Bound::Unbounded, Bound::Unbounded),
+            peak_position: (Bound::Unbounded, Bound::Unbounded),
+            total_streams: (Bound::Unbounded, Bound::Unbounded),
+            artist_name_contains: None,
+            song_name_contains: None,
+            include_artists: None,
+            exclude_artists: HashSet::new(),
+            top_by_streams: None,
+        }
+    }
+
+    /// Keeps songs whose days on the chart are in `range`.
+    pub fn days(mut self, range: impl RangeBounds<u32>) -> Self {
+        self.days = (range.start_bound().cloned(), range.end_bound().cloned());
+        self
+    }
+
+    /// Keeps songs whose peak position is in `range`.
+    pub fn peak_position(mut self, range: impl RangeBounds<u32>) -> Self {
+        self.peak_position = (range.start_bound().cloned(), range.end_bound().cloned());
+        self
+    }
+
+    /// Keeps songs whose total streams are in `range`.
+    pub fn total_streams(mut self, range: impl RangeBounds<u64>) -> Self {
+        self.total_streams = (range.start_bound().cloned(), range.end_bound().cloned());
+        self
+    }
+
+    /// Keeps artists whose name contains `text`, ignoring case.
+    pub fn artist_name_contains(mut self, text: &str) -> Self {
+        self.artist_name_contains = Some(text.to_lowercase());
+        self
+    }
+
+    /// Keeps songs whose name contains `text`, ignoring case.
+    pub fn song_name_contains(mut self, text: &str) -> Self {
+        self.song_name_contains = Some(text.to_lowercase());
+        self
+    }
+
+    /// Keeps only songs by these artists. Names are compared without surrounding whitespace.
+    pub fn include_artists<I, S>(mut self, artists: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let included = self.include_artists.get_or_insert_with(HashSet::new);
+        included.extend(artists.into_iter().map(|artist| artist.as_ref().trim().to_string()));
+        self
+    }
+
+    /// Drops songs by these artists. Names are compared without surrounding whitespace.
+    pub fn exclude_artists<I, S>(mut self, artists: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        self.exclude_artists.extend(artists.into_iter().map(|artist| artist.as_ref().trim().to_string()));
+        self
+    }
+
+    /// Keeps the `n` most streamed of the remaining songs.
+    pub fn top_by_streams(mut self, n: usize) -> Self {
+        self.top_by_streams = Some(n);
+        self
+    }
+
+    /// Whether the query can be decided row by row, i.e. has no top-N limit.
+    pub fn is_streamable(&self) -> bool {
+        self.top_by_streams.is_none()
+    }
+
+    /// Whether `song` passes every per-row condition. Ignores `top_by_streams`.
+    pub fn matches(&self, song: &Song) -> bool {
+        let artist = song.artist_name.trim();
+        self.days.contains(&song.days)
+            && self.peak_position.contains(&song.peak_position)
+            && self.total_streams.contains(&song.total_streams)
+            && self.artist_name_contains.as_ref().is_none_or(|text| artist.to_lowercase().contains(text))
+            && self.song_name_contains.as_ref().is_none_or(|text| song.song_name.to_lowercase().contains(text))
+            && self.include_artists.as_ref().is_none_or(|included| included.contains(artist))
+            && !self.exclude_artists.contains(artist)
+    }
+
+    /// The songs matching the query, in their original order.
+    pub fn apply(&self, songs: Vec<Song>) -> Vec<Song> {
+        let mut selected: Vec<Song> = songs.into_iter().filter(|song| self.matches(song)).collect();
+
+        if let Some(n) = self.top_by_streams {
+            if n < selected.len() {
+                let mut order: Vec<usize> = (0..selected.len()).collect();
+                order.sort_by_key(|&i| (std::cmp::Reverse(selected[i].total_streams), i));
+                let mut keep = vec![false; selected.len()];
+                for &i in &order[..n] {
+                    keep[i] = true;
+                }
+                let mut keep = keep.into_iter();
+                selected.retain(|_| keep.next().unwrap_or(false));
+            }
+        }
+
+        selected
+    }
+}
@@ -0,0 +1,79 @@
+use super::centrality::Centrality;
+use super::graph::{CentralityScores, Graph, Vertex};
+use super::progress::Progress;
+use serde::Serialize;
+use std::cmp::Ordering;
+
+/// One vertex's place in a centrality ranking.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RankedEntry {
+    pub vertex: Vertex,
+    pub song_name: String,
+    pub artist_name: String,
+    pub score: f32,
+    pub total_streams: u64,
+    /// Ties share a rank and the next score takes the following number: 1, 2, 2, 3.
+    pub dense_rank: usize,
+    /// Ties share a rank and the next score skips past them: 1, 2, 2, 4.
+    pub competition_rank: usize,
+}
+
+/// Orders scores from highest to lowest with every NaN last. Unlike `partial_cmp` this never
+/// fails, and unlike `total_cmp` it treats `0.0` and `-0.0` as the same score.
+pub fn compare_scores(a: f32, b: f32) -> Ordering {
+    match (a.is_nan(), b.is_nan()) {
+        (true, true) => Ordering::Equal,
+        (true, false) => Ordering::Greater,
+        (false, true) => Ordering::Less,
+        (false, false) => b.partial_cmp(&a).unwrap_or(Ordering::Equal),
+    }
+}
+
+/// Ranks the vertices of `graph` by `scores`, most central first. Equal scores are ordered by
+/// total streams, highest first, then by song name, artist name and vertex, so the order is
+/// the same on every run. Only the score decides whether two entries share a rank.
+pub fn rank(graph: &Graph, scores: &CentralityScores) -> Vec<RankedEntry> {
+    let mut entries: Vec<RankedEntry> = scores
+        .iter()
+        .map(|(&vertex, (song_name, artist_name, score))| RankedEntry {
+            vertex,
+            song_name: song_name.clone(),
+            artist_name: artist_name.clone(),
+            score: *score,
+            total_streams: graph.vertices.get(vertex).map_or(0, |song| song.total_streams),
+            dense_rank: 0,
+            competition_rank: 0,
+        })
+        .collect();
+
+    entries.sort_by(|a, b| {
+        compare_scores(a.score, b.score)
+            .then_with(|| b.total_streams.cmp(&a.total_streams))
+            .then_with(|| a.song_name.cmp(&b.song_name))
+            .then_with(|| a.artist_name.cmp(&b.artist_name))
+            .then_with(|| a.vertex.cmp(&b.vertex))
+    });
+
+    for i in 0..entries.len() {
+        if i > 0 && compare_scores(entries[i - 1].score, entries[i].score) == Ordering::Equal {
+            entries[i].dense_rank = entries[i - 1].dense_rank;
+            entries[i].competition_rank = entries[i - 1].competition_rank;
+        } else {
+            entries[i].dense_rank = if i == 0 { 1 } else { entries[i - 1].dense_rank + 1 };
+            entries[i].competition_rank = i + 1;
+        }
+    }
+    entries
+}
+
+impl Graph {
+    /// Every vertex ranked by `metric`, most central first. See [`rank`] for the tie-breaks.
+    pub fn ranking(&self, metric: Centrality) -> Vec<RankedEntry> {
+        rank(self, &self.centrality(metric))
+    }
+
+    /// `ranking` over `centrality_observed`, so a cancelled run ranks only the scored vertices.
+    pub fn ranking_observed(&self, metric: Centrality, progress: &dyn Progress) -> Vec<RankedEntry> {
+        rank(self, &self.centrality_observed(metric, progress))
+    }
+}
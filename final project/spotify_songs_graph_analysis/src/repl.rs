@@ -0,0 +1,228 @@
+use super::centrality::Centrality;
+use super::graph::{Graph, Vertex};
+use super::ranking::RankedEntry;
+use rustyline::completion::Completer;
+use rustyline::error::ReadlineError;
+use rustyline::highlight::Highlighter;
+use rustyline::hint::Hinter;
+use rustyline::validate::Validator;
+use rustyline::{Context, Editor, Helper};
+use std::collections::HashMap;
+use std::fmt::Write;
+
+const COMMANDS: [&str; 8] = ["find", "neighbours", "path", "central", "stats", "song", "help", "quit"];
+
+const HELP: &str = "\
+find <text>                 songs whose name contains the text
+song <id>                   one song
+neighbours <id> [k]         the k most heavily weighted neighbours (default 10)
+path <a> <b>                shortest path between two songs
+central [k] [metric]        top k songs by closeness, harmonic or wasserman-faust (default 10, closeness)
+stats                       summary statistics of the graph
+help                        this list
+quit                        leave the shell";
+
+/// Interactive shell over one graph. Rankings are computed on first use and kept.
+pub struct Repl {
+    graph: Graph,
+    rankings: HashMap<Centrality, Vec<RankedEntry>>,
+}
+
+/// What the shell should do after a line has been executed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    Print(String),
+    Quit,
+}
+
+impl Repl {
+    /// Shell over `graph` with no rankings computed yet.
+    pub fn new(graph: Graph) -> Self {
+        Repl { graph, rankings: HashMap::new() }
+    }
+
+    /// Reads and executes commands from the terminal until `quit` or end of input, completing
+    /// command names and, after `find`, song names with tab.
+    pub fn run(&mut self) -> rustyline::Result<()> {
+        let mut editor: Editor<SongCompleter, _> = Editor::new()?;
+        editor.set_helper(Some(SongCompleter::new(&self.graph)));
+        println!("{} songs loaded. Type `help` for commands.", self.graph.vertices.len());
+
+        loop {
+            match editor.readline("songs> ") {
+                Ok(line) => {
+                    editor.add_history_entry(line.as_str())?;
+                    match self.execute(&line) {
+                        Ok(Outcome::Print(text)) => println!("{}", text),
+                        Ok(Outcome::Quit) => return Ok(()),
+                        Err(e) => println!("Error: {}", e),
+                    }
+                }
+                Err(ReadlineError::Interrupted) => continue,
+                Err(ReadlineError::Eof) => return Ok(()),
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    /// Executes one command line and returns the text to print.
+    pub fn execute(&mut self, line: &str) -> Result<Outcome, String> {
+        let line = line.trim();
+        let (command, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+        let rest = rest.trim();
+        let args: Vec<&str> = rest.split_whitespace().collect();
+
+        let text = match command {
+            "" => String::new(),
+            "help" => HELP.to_string(),
+            "quit" | "exit" => return Ok(Outcome::Quit),
+            "find" if !rest.is_empty() => self.find(rest),
+            "song" if args.len() == 1 => {
+                let vertex = self.vertex(args[0])?;
+                self.describe(vertex)
+            }
+            "neighbours" if !args.is_empty() && args.len() <= 2 => {
+                let vertex = self.vertex(args[0])?;
+                self.neighbours(vertex, count(args.get(1))?)
+            }
+            "path" if args.len() == 2 => {
+                let (from, to) = (self.vertex(args[0])?, self.vertex(args[1])?);
+                self.path(from, to)
+            }
+            "central" if args.len() <= 2 => {
+                let metric = match args.get(1) {
+                    Some(name) => name.parse()?,
+                    None => Centrality::Closeness,
+                };
+                self.central(count(args.first())?, metric)
+            }
+            "stats" if args.is_empty() => self.graph.stats().to_string(),
+            _ if COMMANDS.contains(&command) => return Err(format!("wrong arguments for `{}`, see `help`", command)),
+            _ => return Err(format!("unknown command `{}`, see `help`", command)),
+        };
+        Ok(Outcome::Print(text))
+    }
+
+    fn find(&self, text: &str) -> String {
+        let text = text.to_lowercase();
+        let mut output = String::new();
+        for (vertex, song) in self.graph.vertices.iter().enumerate() {
+            if song.song_name.to_lowercase().contains(&text) {
+                let _ = writeln!(output, "{}", self.describe(vertex));
+            }
+        }
+        if output.is_empty() {
+            "No songs found".to_string()
+        } else {
+            output.trim_end().to_string()
+        }
+    }
+
+    fn neighbours(&self, vertex: Vertex, k: usize) -> String {
+        let mut neighbours = self.graph.adjacency_list[vertex].clone();
+        neighbours.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        let lines: Vec<String> =
+            neighbours.iter().take(k).map(|&(v, weight)| format!("{}, Weight: {}", self.describe(v), weight)).collect();
+        if lines.is_empty() {
+            "No neighbours".to_string()
+        } else {
+            lines.join("\n")
+        }
+    }
+
+    fn path(&self, from: Vertex, to: Vertex) -> String {
+        match self.graph.shortest_path(from, to) {
+            Some((distance, vertices)) => {
+                let mut output = format!("Distance: {:.2}", distance);
+                for vertex in vertices {
+                    let _ = write!(output, "\n{}", self.describe(vertex));
+                }
+                output
+            }
+            None => format!("No path from {} to {}", from, to),
+        }
+    }
+
+    fn central(&mut self, k: usize, metric: Centrality) -> String {
+        let graph = &self.graph;
+        let ranking = self.rankings.entry(metric).or_insert_with(|| graph.ranking(metric));
+        ranking
+            .iter()
+            .take(k)
+            .map(|entry| {
+                format!(
+                    "Depth {}: [{}] Song: {}, Artist: {}, {}: {:.4}",
+                    entry.competition_rank, entry.vertex, entry.song_name, entry.artist_name, metric, entry.score
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    fn vertex(&self, text: &str) -> Result<Vertex, String> {
+        match text.parse::<Vertex>() {
+            Ok(vertex) if vertex < self.graph.vertices.len() => Ok(vertex),
+            Ok(vertex) => Err(format!("no song {}", vertex)),
+            Err(_) => Err(format!("invalid song id `{}`", text)),
+        }
+    }
+
+    fn describe(&self, vertex: Vertex) -> String {
+        let song = &self.graph.vertices[vertex];
+        format!("[{}] Song: {}, Artist: {}", vertex, song.song_name, song.artist_name)
+    }
+}
+
+fn count(text: Option<&&str>) -> Result<usize, String> {
+    match text {
+        Some(text) => text.parse().map_err(|_| format!("invalid count `{}`", text)),
+        None => Ok(10),
+    }
+}
+
+/// Tab completion of command names, and of song names after `find`.
+pub struct SongCompleter {
+    song_names: Vec<String>,
+}
+
+impl SongCompleter {
+    /// Completer offering the distinct song names of `graph`.
+    pub fn new(graph: &Graph) -> Self {
+        let mut song_names: Vec<String> = graph.vertices.iter().map(|song| song.song_name.clone()).collect();
+        song_names.sort();
+        song_names.dedup();
+        SongCompleter { song_names }
+    }
+
+    /// Where the completed word starts in `line` and the candidates for it, given the text
+    /// up to the cursor. Song names match by prefix, ignoring case.
+    pub fn candidates(&self, line: &str) -> (usize, Vec<String>) {
+        match line.split_once(' ') {
+            None => (0, COMMANDS.iter().filter(|command| command.starts_with(line)).map(|command| command.to_string()).collect()),
+            Some(("find", prefix)) => {
+                let start = line.len() - prefix.len();
+                let prefix = prefix.to_lowercase();
+                (start, self.song_names.iter().filter(|name| name.to_lowercase().starts_with(&prefix)).cloned().collect())
+            }
+            Some(_) => (line.len(), vec![]),
+        }
+    }
+}
+
+impl Completer for SongCompleter {
+    type Candidate = String;
+
+    fn complete(&self, line: &str, pos: usize, _ctx: &Context<'_>) -> rustyline::Result<(usize, Vec<String>)> {
+        Ok(self.candidates(&line[..pos]))
+    }
+}
+
+impl Hinter for SongCompleter {
+    type Hint = String;
+}
+
+impl Highlighter for SongCompleter {}
+
+impl Validator for SongCompleter {}
+
+impl Helper for SongCompleter {}
@@ -0,0 +1,47 @@
+use super::song::Song;
+use rand::{rngs::StdRng, seq::index, SeedableRng};
+
+/// Picks `size` songs uniformly at random, returned in their original order.
+pub fn uniform_subset(songs: &[Song], size: usize, seed: u64) -> Vec<Song> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut picked = index::sample(&mut rng, songs.len(), size.min(songs.len())).into_vec();
+    picked.sort_unstable();
+    picked.into_iter().map(|i| songs[i].clone()).collect()
+}
+
+/// Splits the songs into `strata` equally sized bins by total_streams and samples each bin
+/// in proportion to its size, so the subset keeps the spread of hits and minor songs.
+pub fn stratified_subset(songs: &[Song], size: usize, strata: usize, seed: u64) -> Vec<Song> {
+    let size = size.min(songs.len());
+    let strata = strata.clamp(1, songs.len().max(1));
+    let mut rng = StdRng::seed_from_u64(seed);
+
+    let mut by_streams: Vec<usize> = (0..songs.len()).collect();
+    by_streams.sort_by_key(|&i| (songs[i].total_streams, i));
+
+    let bins: Vec<&[usize]> = (0..strata)
+        .map(|s| &by_streams[s * songs.len() / strata..(s + 1) * songs.len() / strata])
+        .collect();
+
+    // largest remainder allocation so the quotas add up to exactly `size`
+    let mut quotas: Vec<(usize, usize)> = bins.iter().map(|bin| (bin.len() * size / songs.len().max(1), bin.len() * size % songs.len().max(1))).collect();
+    let mut missing = size - quotas.iter().map(|&(quota, _)| quota).sum::<usize>();
+    let mut by_remainder: Vec<usize> = (0..strata).collect();
+    by_remainder.sort_by_key(|&s| (std::cmp::Reverse(quotas[s].1), s));
+    for s in by_remainder {
+        if missing == 0 {
+            break;
+        }
+        if quotas[s].0 < bins[s].len() {
+            quotas[s].0 += 1;
+            missing -= 1;
+        }
+    }
+
+    let mut picked = vec![];
+    for (bin, &(quota, _)) in bins.iter().zip(&quotas) {
+        picked.extend(index::sample(&mut rng, bin.len(), quota).into_iter().map(|i| bin[i]));
+    }
+    picked.sort_unstable();
+    picked.into_iter().map(|i| songs[i].clone()).collect()
+}
@@ -0,0 +1,277 @@
+use super::centrality::Centrality;
+use super::graph::{Graph, Vertex, Weight};
+use super::ranking::RankedEntry;
+use super::song::Song;
+use serde::Serialize;
+use serde_json::json;
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream, ToSocketAddrs};
+use std::time::Duration;
+
+const DEFAULT_LIMIT: usize = 10;
+// Connections that send or accept nothing for this long are dropped, so one idle client
+// cannot hold up the requests queued behind it.
+const IO_TIMEOUT: Duration = Duration::from_secs(5);
+// Longest request or header line, and most header lines, read before answering 431.
+const MAX_LINE: usize = 8 * 1024;
+const MAX_HEADERS: usize = 100;
+
+/// Status code and JSON body of an answered request.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Response {
+    pub status: u16,
+    pub body: String,
+}
+
+impl Response {
+    fn ok(body: impl Serialize) -> Self {
+        Response { status: 200, body: serde_json::to_string(&body).unwrap_or_else(|_| "null".to_string()) }
+    }
+
+    fn error(status: u16, message: impl Into<String>) -> Self {
+        Response { status, body: json!({ "error": message.into() }).to_string() }
+    }
+
+    fn reason(&self) -> &'static str {
+        match self.status {
+            200 => "OK",
+            400 => "Bad Request",
+            404 => "Not Found",
+            405 => "Method Not Allowed",
+            431 => "Request Header Fields Too Large",
+            _ => "Internal Server Error",
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct SongEntry<'a> {
+    vertex: Vertex,
+    #[serde(flatten)]
+    song: &'a Song,
+}
+
+#[derive(Serialize)]
+struct Neighbour<'a> {
+    vertex: Vertex,
+    weight: Weight,
+    #[serde(flatten)]
+    song: &'a Song,
+}
+
+/// Answers JSON queries about one graph over HTTP on localhost. Centrality rankings are
+/// computed on first request and kept for later ones.
+///
+/// | Request | Answer |
+/// | --- | --- |
+/// | `GET /songs?name=&artist=&limit=` | songs whose names contain the given text, ignoring case |
+/// | `GET /songs/{vertex}` | one song |
+/// | `GET /neighbours/{vertex}?limit=` | the most heavily weighted neighbours of a song |
+/// | `GET /path?from=&to=` | the shortest path between two songs and its length |
+/// | `GET /central?metric=&limit=` | the top of a centrality ranking |
+///
+/// Browsers only let other sites read the answers after `with_allow_origin`.
+pub struct GraphServer {
+    graph: Graph,
+    rankings: HashMap<Centrality, Vec<RankedEntry>>,
+    allow_origin: Option<String>,
+}
+
+impl GraphServer {
+    /// Server for `graph`. Nothing is computed until the first request.
+    pub fn new(graph: Graph) -> Self {
+        GraphServer { graph, rankings: HashMap::new(), allow_origin: None }
+    }
+
+    /// Sends `Access-Control-Allow-Origin: origin` with every response, so pages served from
+    /// `origin` can query the server. `*` allows every site.
+    pub fn with_allow_origin(mut self, origin: impl Into<String>) -> Self {
+        self.allow_origin = Some(origin.into());
+        self
+    }
+
+    /// Accepts connections on `address` until the process ends, one request at a time. Clients
+    /// idle for more than five seconds are disconnected.
+    pub fn serve(&mut self, address: impl ToSocketAddrs) -> std::io::Result<()> {
+        self.serve_listener(TcpListener::bind(address)?)
+    }
+
+    /// `serve` on a listener that is already bound, such as one on port 0.
+    pub fn serve_listener(&mut self, listener: TcpListener) -> std::io::Result<()> {
+        for stream in listener.incoming() {
+            if let Err(e) = stream.and_then(|stream| self.respond(stream)) {
+                eprintln!("Request failed: {}", e);
+            }
+        }
+        Ok(())
+    }
+
+    fn respond(&mut self, mut stream: TcpStream) -> std::io::Result<()> {
+        stream.set_read_timeout(Some(IO_TIMEOUT))?;
+        stream.set_write_timeout(Some(IO_TIMEOUT))?;
+        let response = match read_head(&mut BufReader::new(&stream))? {
+            Some(request_line) => {
+                let mut parts = request_line.split_whitespace();
+                match (parts.next(), parts.next()) {
+                    (Some(method), Some(target)) => self.handle_request(method, target),
+                    _ => Response::error(400, "malformed request line"),
+                }
+            }
+            None => Response::error(431, format!("request lines are limited to {} bytes and {} headers", MAX_LINE, MAX_HEADERS)),
+        };
+        let allow_origin = self.allow_origin.as_ref().map_or(String::new(), |origin| format!("Access-Control-Allow-Origin: {}\r\n", origin));
+        write!(
+            stream,
+            "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\n{}Content-Length: {}\r\nConnection: close\r\n\r\n{}",
+            response.status,
+            response.reason(),
+            allow_origin,
+            response.body.len(),
+            response.body
+        )?;
+        stream.flush()
+    }
+
+    /// Answers one request given its method and target, e.g. `GET` and `/path?from=1&to=2`.
+    pub fn handle_request(&mut self, method: &str, target: &str) -> Response {
+        if method != "GET" {
+            return Response::error(405, "only GET is supported");
+        }
+        let (path, query) = target.split_once('?').unwrap_or((target, ""));
+        let params: HashMap<String, String> = query
+            .split('&')
+            .filter(|pair| !pair.is_empty())
+            .map(|pair| {
+                let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+                (percent_decode(key), percent_decode(value))
+            })
+            .collect();
+        let segments: Vec<&str> = path.split('/').filter(|segment| !segment.is_empty()).collect();
+
+        let result = match segments.as_slice() {
+            ["songs"] => self.find_songs(&params),
+            ["songs", vertex] => self.vertex(vertex).map(|vertex| Response::ok(self.song_entry(vertex))),
+            ["neighbours", vertex] => self.vertex(vertex).and_then(|vertex| self.neighbours(vertex, &params)),
+            ["path"] => self.path(&params),
+            ["central"] => self.central(&params),
+            _ => Err(Response::error(404, format!("no route for {}", path))),
+        };
+        result.unwrap_or_else(|error| error)
+    }
+
+    fn find_songs(&self, params: &HashMap<String, String>) -> Result<Response, Response> {
+        let name = params.get("name").map(|text| text.to_lowercase());
+        let artist = params.get("artist").map(|text| text.to_lowercase());
+        let limit = limit(params)?;
+        let songs: Vec<SongEntry> = self
+            .graph
+            .vertices
+            .iter()
+            .enumerate()
+            .filter(|(_, song)| name.as_ref().is_none_or(|name| song.song_name.to_lowercase().contains(name)))
+            .filter(|(_, song)| artist.as_ref().is_none_or(|artist| song.artist_name.to_lowercase().contains(artist)))
+            .take(limit)
+            .map(|(vertex, song)| SongEntry { vertex, song })
+            .collect();
+        Ok(Response::ok(songs))
+    }
+
+    fn neighbours(&self, vertex: Vertex, params: &HashMap<String, String>) -> Result<Response, Response> {
+        let mut neighbours: Vec<Neighbour> = self.graph.adjacency_list[vertex]
+            .iter()
+            .map(|&(v, weight)| Neighbour { vertex: v, weight, song: &self.graph.vertices[v] })
+            .collect();
+        neighbours.sort_by(|a, b| b.weight.cmp(&a.weight).then_with(|| a.vertex.cmp(&b.vertex)));
+        neighbours.truncate(limit(params)?);
+        Ok(Response::ok(neighbours))
+    }
+
+    fn path(&self, params: &HashMap<String, String>) -> Result<Response, Response> {
+        let endpoint = |name: &str| params.get(name).ok_or_else(|| Response::error(400, format!("missing `{}`", name)));
+        let from = self.vertex(endpoint("from")?)?;
+        let to = self.vertex(endpoint("to")?)?;
+        match self.graph.shortest_path(from, to) {
+            Some((distance, vertices)) => {
+                let songs: Vec<SongEntry> = vertices.into_iter().map(|vertex| self.song_entry(vertex)).collect();
+                Ok(Response::ok(json!({ "distance": distance, "songs": songs })))
+            }
+            None => Err(Response::error(404, format!("no path from {} to {}", from, to))),
+        }
+    }
+
+    fn central(&mut self, params: &HashMap<String, String>) -> Result<Response, Response> {
+        let metric: Centrality = match params.get("metric") {
+            Some(name) => name.parse().map_err(|e: String| Response::error(400, e))?,
+            None => Centrality::Closeness,
+        };
+        let limit = limit(params)?;
+        let graph = &self.graph;
+        let ranking = self.rankings.entry(metric).or_insert_with(|| graph.ranking(metric));
+        Ok(Response::ok(&ranking[..limit.min(ranking.len())]))
+    }
+
+    fn vertex(&self, text: &str) -> Result<Vertex, Response> {
+        match text.parse::<Vertex>() {
+            Ok(vertex) if vertex < self.graph.vertices.len() => Ok(vertex),
+            Ok(vertex) => Err(Response::error(404, format!("no song {}", vertex))),
+            Err(_) => Err(Response::error(400, format!("invalid vertex `{}`", text))),
+        }
+    }
+
+    fn song_entry(&self, vertex: Vertex) -> SongEntry<'_> {
+        SongEntry { vertex, song: &self.graph.vertices[vertex] }
+    }
+}
+
+fn limit(params: &HashMap<String, String>) -> Result<usize, Response> {
+    match params.get("limit") {
+        Some(text) => text.parse().map_err(|_| Response::error(400, format!("invalid limit `{}`", text))),
+        None => Ok(DEFAULT_LIMIT),
+    }
+}
+
+// Decodes `%XX` escapes and `+` as used in query strings; malformed escapes are kept as is.
+fn percent_decode(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let escaped = bytes.get(i + 1..i + 3).and_then(|hex| u8::from_str_radix(std::str::from_utf8(hex).ok()?, 16).ok());
+        match (bytes[i], escaped) {
+            (b'+', _) => decoded.push(b' '),
+            (b'%', Some(byte)) => {
+                decoded.push(byte);
+                i += 2;
+            }
+            (byte, _) => decoded.push(byte),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+// Reads the request line and skips the headers, which are not needed but must be read before
+// replying. `None` when a line is longer than `MAX_LINE` or there are over `MAX_HEADERS` headers.
+fn read_head(reader: &mut impl BufRead) -> std::io::Result<Option<String>> {
+    let mut request_line = String::new();
+    if !read_limited_line(reader, &mut request_line)? {
+        return Ok(None);
+    }
+    let mut header = String::new();
+    for _ in 0..=MAX_HEADERS {
+        header.clear();
+        if !read_limited_line(reader, &mut header)? {
+            return Ok(None);
+        }
+        if header.trim_end().is_empty() {
+            return Ok(Some(request_line));
+        }
+    }
+    Ok(None)
+}
+
+// Appends one line to `line`, reading at most one byte past `MAX_LINE`; false when it is longer.
+fn read_limited_line(reader: &mut impl BufRead, line: &mut String) -> std::io::Result<bool> {
+    Ok(reader.take(MAX_LINE as u64 + 1).read_line(line)? <= MAX_LINE)
+}
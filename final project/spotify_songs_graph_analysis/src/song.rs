@@ -1,9 +1,10 @@
-use serde::Deserialize;
-use csv::ReaderBuilder;
+use serde::{Deserialize, Serialize};
+use csv::{DeserializeRecordsIntoIter, ReaderBuilder};
 use std::fs::File;
 use std::error::Error;
 
-#[derive(Debug, Deserialize, Clone)]
+/// One row of the chart dataset.
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Song {
     pub artist_name: String,
     pub song_name: String,
@@ -12,12 +13,11 @@ pub struct Song {
     pub total_streams: u64, 
 }
 
+/// Reads every song from a headered CSV file such as `Spotify_final_dataset.csv`.
 pub fn load_songs_from_csv(file_path: &str) -> Result<Vec<Song>, Box<dyn Error>> {
-    let file = File::open(file_path)?;
-    let mut rdr = ReaderBuilder::new().has_headers(true).from_reader(file);
     let mut songs = vec![];
 
-    for result in rdr.deserialize() {
+    for result in stream_songs_from_csv(file_path)? {
         let song: Song = result?;
         songs.push(song);
     }
@@ -25,3 +25,9 @@ pub fn load_songs_from_csv(file_path: &str) -> Result<Vec<Song>, Box<dyn Error>>
     Ok(songs)
 }
 
+/// Lazily reads songs one row at a time, so only the rows a caller keeps stay in memory.
+pub fn stream_songs_from_csv(file_path: &str) -> Result<DeserializeRecordsIntoIter<File, Song>, Box<dyn Error>> {
+    let file = File::open(file_path)?;
+    let rdr = ReaderBuilder::new().has_headers(true).from_reader(file);
+    Ok(rdr.into_deserialize())
+}
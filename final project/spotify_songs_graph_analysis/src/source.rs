@@ -0,0 +1,325 @@
+use super::song::Song;
+use csv::{ReaderBuilder, StringRecord};
+use serde_json::Deserializer;
+use std::error::Error;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+
+/// Songs read one at a time from a source, each row either a song or the reason it failed.
+pub type SongIter = Box<dyn Iterator<Item = Result<Song, Box<dyn Error>>>>;
+
+/// Anything songs can be read from.
+pub trait SongSource {
+    /// Opens the source and returns its songs in file order.
+    fn songs(&self) -> Result<SongIter, Box<dyn Error>>;
+
+    /// Reads every song, stopping at the first bad row.
+    fn load(&self) -> Result<Vec<Song>, Box<dyn Error>> {
+        self.songs()?.collect()
+    }
+}
+
+/// Where a `Song` field is found in a delimited file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Column {
+    /// Header name, matched after trimming whitespace.
+    Name(String),
+    /// Zero based position, for files with or without headers.
+    Index(usize),
+}
+
+/// Columns holding each `Song` field. The default expects headers named after the fields.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ColumnMapping {
+    pub artist_name: Column,
+    pub song_name: Column,
+    pub days: Column,
+    pub peak_position: Column,
+    pub total_streams: Column,
+}
+
+impl Default for ColumnMapping {
+    fn default() -> Self {
+        ColumnMapping {
+            artist_name: Column::Name("artist_name".to_string()),
+            song_name: Column::Name("song_name".to_string()),
+            days: Column::Name("days".to_string()),
+            peak_position: Column::Name("peak_position".to_string()),
+            total_streams: Column::Name("total_streams".to_string()),
+        }
+    }
+}
+
+impl ColumnMapping {
+    /// Points `field` (one of the `Song` field names) at another column.
+    pub fn set(&mut self, field: &str, column: Column) -> Result<(), Box<dyn Error>> {
+        let slot = match field {
+            "artist_name" => &mut self.artist_name,
+            "song_name" => &mut self.song_name,
+            "days" => &mut self.days,
+            "peak_position" => &mut self.peak_position,
+            "total_streams" => &mut self.total_streams,
+            _ => return Err(format!("unknown song field `{}`", field).into()),
+        };
+        *slot = column;
+        Ok(())
+    }
+
+    fn resolve(&self, headers: Option<&StringRecord>) -> Result<[usize; 5], Box<dyn Error>> {
+        let find = |field: &str, column: &Column| -> Result<usize, Box<dyn Error>> {
+            match (column, headers) {
+                (Column::Index(index), _) => Ok(*index),
+                (Column::Name(name), Some(headers)) => headers
+                    .iter()
+                    .position(|header| header.trim() == name)
+                    .ok_or_else(|| format!("no column `{}` for field `{}`", name, field).into()),
+                (Column::Name(name), None) => Err(format!("field `{}` maps to column `{}` but the file has no headers", field, name).into()),
+            }
+        };
+        Ok([
+            find("artist_name", &self.artist_name)?,
+            find("song_name", &self.song_name)?,
+            find("days", &self.days)?,
+            find("peak_position", &self.peak_position)?,
+            find("total_streams", &self.total_streams)?,
+        ])
+    }
+}
+
+/// CSV, TSV or any other single byte delimited text file.
+#[derive(Debug, Clone)]
+pub struct DelimitedSource {
+    pub path: PathBuf,
+    pub delimiter: u8,
+    pub has_headers: bool,
+    pub mapping: ColumnMapping,
+}
+
+impl DelimitedSource {
+    /// Comma separated file with a header row and the default mapping.
+    pub fn csv(path: impl AsRef<Path>) -> Self {
+        DelimitedSource {
+            path: path.as_ref().to_path_buf(),
+            delimiter: b',',
+            has_headers: true,
+            mapping: ColumnMapping::default(),
+        }
+    }
+
+    /// Tab separated file with a header row and the default mapping.
+    pub fn tsv(path: impl AsRef<Path>) -> Self {
+        DelimitedSource { delimiter: b'\t', ..Self::csv(path) }
+    }
+
+    /// Reads the fields from the columns in `mapping` instead of the default header names.
+    pub fn with_mapping(mut self, mapping: ColumnMapping) -> Self {
+        self.mapping = mapping;
+        self
+    }
+
+    /// Treats the first row as data; every field must then be mapped by `Column::Index`.
+    pub fn without_headers(mut self) -> Self {
+        self.has_headers = false;
+        self
+    }
+}
+
+impl SongSource for DelimitedSource {
+    fn songs(&self) -> Result<SongIter, Box<dyn Error>> {
+        let file = File::open(&self.path)?;
+        let mut rdr = ReaderBuilder::new().delimiter(self.delimiter).has_headers(self.has_headers).from_reader(file);
+        let headers = if self.has_headers { Some(rdr.headers()?.clone()) } else { None };
+        let columns = self.mapping.resolve(headers.as_ref())?;
+
+        let rows = rdr.into_records().enumerate().map(move |(row, record)| {
+            let record = record?;
+            let field = |name: &str, index: usize| -> Result<&str, Box<dyn Error>> {
+                record
+                    .get(columns[index])
+                    .map(str::trim)
+                    .ok_or_else(|| format!("row {}: missing column {} for `{}`", row + 1, columns[index], name).into())
+            };
+            Ok(Song {
+                artist_name: field("artist_name", 0)?.to_string(),
+                song_name: field("song_name", 1)?.to_string(),
+                days: parse_count(row, "days", field("days", 2)?)?,
+                peak_position: parse_count(row, "peak_position", field("peak_position", 3)?)?,
+                total_streams: parse_count(row, "total_streams", field("total_streams", 4)?)?,
+            })
+        });
+        Ok(Box::new(rows))
+    }
+}
+
+// Accepts plain integers as well as exports that write them as "1,234" or "302.0".
+fn parse_count<T: TryFrom<u64>>(row: usize, name: &str, text: &str) -> Result<T, Box<dyn Error>> {
+    let cleaned = text.replace(',', "");
+    cleaned
+        .parse::<u64>()
+        .ok()
+        .or_else(|| cleaned.parse::<f64>().ok().filter(|value| *value >= 0.0 && value.fract() == 0.0).map(|value| value as u64))
+        .and_then(|value| T::try_from(value).ok())
+        .ok_or_else(|| format!("row {}: invalid `{}` value `{}`", row + 1, name, text).into())
+}
+
+/// JSON file holding either one array of song objects or one object per line (JSON Lines).
+#[derive(Debug, Clone)]
+pub struct JsonSource {
+    pub path: PathBuf,
+    pub lines: bool,
+}
+
+impl JsonSource {
+    /// A file holding one JSON array of songs.
+    pub fn array(path: impl AsRef<Path>) -> Self {
+        JsonSource { path: path.as_ref().to_path_buf(), lines: false }
+    }
+
+    /// A JSON Lines file with one song object per line.
+    pub fn lines(path: impl AsRef<Path>) -> Self {
+        JsonSource { path: path.as_ref().to_path_buf(), lines: true }
+    }
+}
+
+impl SongSource for JsonSource {
+    fn songs(&self) -> Result<SongIter, Box<dyn Error>> {
+        let reader = BufReader::new(File::open(&self.path)?);
+        if self.lines {
+            let rows = Deserializer::from_reader(reader).into_iter::<Song>().map(|song| song.map_err(|e| e.into()));
+            Ok(Box::new(rows))
+        } else {
+            let songs: Vec<Song> = serde_json::from_reader(reader)?;
+            Ok(Box::new(songs.into_iter().map(Ok)))
+        }
+    }
+}
+
+const COLUMNAR_MAGIC: &[u8; 8] = b"SONGCOL1";
+
+/// Binary columnar file written by `write_columnar`: the magic bytes `SONGCOL1`, the row
+/// count as a little endian u64, then each field's column in `Song` order. Strings are a
+/// little endian u32 byte length followed by UTF-8, days and peak_position are u32 and
+/// total_streams u64, all little endian.
+#[derive(Debug, Clone)]
+pub struct ColumnarSource {
+    pub path: PathBuf,
+}
+
+impl ColumnarSource {
+    /// Columnar file at `path`; nothing is read until `songs` is called.
+    pub fn new(path: impl AsRef<Path>) -> Self {
+        ColumnarSource { path: path.as_ref().to_path_buf() }
+    }
+}
+
+impl SongSource for ColumnarSource {
+    fn songs(&self) -> Result<SongIter, Box<dyn Error>> {
+        let mut reader = BufReader::new(File::open(&self.path)?);
+        let mut magic = [0u8; 8];
+        reader.read_exact(&mut magic)?;
+        if &magic != COLUMNAR_MAGIC {
+            return Err(format!("{} is not a columnar song file", self.path.display()).into());
+        }
+        let rows = read_u64(&mut reader)? as usize;
+
+        let mut remaining = reader.get_ref().metadata()?.len().saturating_sub(16);
+        let artist_names = read_strings(&mut reader, rows, &mut remaining)?;
+        let song_names = read_strings(&mut reader, rows, &mut remaining)?;
+        let days = (0..rows).map(|_| read_u32(&mut reader)).collect::<Result<Vec<_>, _>>()?;
+        let peak_positions = (0..rows).map(|_| read_u32(&mut reader)).collect::<Result<Vec<_>, _>>()?;
+        let total_streams = (0..rows).map(|_| read_u64(&mut reader)).collect::<Result<Vec<_>, _>>()?;
+
+        let songs = artist_names
+            .into_iter()
+            .zip(song_names)
+            .zip(days.into_iter().zip(peak_positions).zip(total_streams))
+            .map(|((artist_name, song_name), ((days, peak_position), total_streams))| {
+                Ok(Song { artist_name, song_name, days, peak_position, total_streams })
+            });
+        Ok(Box::new(songs))
+    }
+}
+
+/// Writes songs in the format read by `ColumnarSource`.
+pub fn write_columnar(path: impl AsRef<Path>, songs: &[Song]) -> Result<(), Box<dyn Error>> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    writer.write_all(COLUMNAR_MAGIC)?;
+    writer.write_all(&(songs.len() as u64).to_le_bytes())?;
+    for text in songs.iter().map(|s| &s.artist_name).chain(songs.iter().map(|s| &s.song_name)) {
+        writer.write_all(&(text.len() as u32).to_le_bytes())?;
+        writer.write_all(text.as_bytes())?;
+    }
+    for song in songs {
+        writer.write_all(&song.days.to_le_bytes())?;
+    }
+    for song in songs {
+        writer.write_all(&song.peak_position.to_le_bytes())?;
+    }
+    for song in songs {
+        writer.write_all(&song.total_streams.to_le_bytes())?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+fn read_u32(reader: &mut impl Read) -> Result<u32, Box<dyn Error>> {
+    let mut bytes = [0u8; 4];
+    reader.read_exact(&mut bytes)?;
+    Ok(u32::from_le_bytes(bytes))
+}
+
+fn read_u64(reader: &mut impl Read) -> Result<u64, Box<dyn Error>> {
+    let mut bytes = [0u8; 8];
+    reader.read_exact(&mut bytes)?;
+    Ok(u64::from_le_bytes(bytes))
+}
+
+/// Reads `count` length prefixed strings. `remaining` is the number of unread bytes in the
+/// file, so a corrupt length is an error instead of a huge allocation.
+fn read_strings(reader: &mut impl Read, count: usize, remaining: &mut u64) -> Result<Vec<String>, Box<dyn Error>> {
+    let mut strings = Vec::with_capacity(count.min(1 << 16));
+    for _ in 0..count {
+        let length = read_u32(reader)? as u64;
+        *remaining = remaining.saturating_sub(4);
+        if length > *remaining {
+            return Err(format!("string of {} bytes runs past the end of the file", length).into());
+        }
+        *remaining -= length;
+        let mut bytes = vec![0u8; length as usize];
+        reader.read_exact(&mut bytes)?;
+        strings.push(String::from_utf8(bytes)?);
+    }
+    Ok(strings)
+}
+
+/// Format name for `path` from its extension: `tsv`, `json`, `jsonl`/`ndjson` and `songcol`
+/// are recognised in any case, and everything else, including no extension, is `csv`.
+pub fn format_for_path(path: impl AsRef<Path>) -> &'static str {
+    match path.as_ref().extension().and_then(|extension| extension.to_str()).map(str::to_lowercase).as_deref() {
+        Some("tsv") => "tsv",
+        Some("json") => "json",
+        Some("jsonl") | Some("ndjson") => "jsonl",
+        Some("songcol") => "songcol",
+        _ => "csv",
+    }
+}
+
+/// Picks a source from the file extension with `format_for_path` and the default mapping.
+pub fn source_for_path(path: impl AsRef<Path>) -> Box<dyn SongSource> {
+    source_for_format(&path, format_for_path(&path), ColumnMapping::default()).expect("format_for_path only returns known formats")
+}
+
+/// Source for `path` read as `format`, one of `csv`, `tsv`, `json`, `jsonl`/`ndjson` and
+/// `songcol`/`columnar`. `mapping` applies to the delimited formats.
+pub fn source_for_format(path: impl AsRef<Path>, format: &str, mapping: ColumnMapping) -> Result<Box<dyn SongSource>, String> {
+    let path = path.as_ref();
+    match format {
+        "csv" => Ok(Box::new(DelimitedSource::csv(path).with_mapping(mapping))),
+        "tsv" => Ok(Box::new(DelimitedSource::tsv(path).with_mapping(mapping))),
+        "json" => Ok(Box::new(JsonSource::array(path))),
+        "jsonl" | "ndjson" => Ok(Box::new(JsonSource::lines(path))),
+        "songcol" | "columnar" => Ok(Box::new(ColumnarSource::new(path))),
+        other => Err(format!("unknown input format `{}`, expected csv, tsv, json, jsonl or songcol", other)),
+    }
+}
@@ -0,0 +1,264 @@
+use super::graph::{Graph, Vertex};
+use super::progress::{Progress, Silent};
+use super::weights::{histogram, HistogramBin};
+use rand::{rngs::StdRng, seq::index, SeedableRng};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashSet};
+use std::fmt;
+
+// Graphs up to this size get exact path statistics from `stats()`, larger ones are sampled.
+const EXACT_PATH_LIMIT: usize = 1000;
+const DEFAULT_PATH_SAMPLES: usize = 100;
+const DEFAULT_SEED: u64 = 210;
+const STRENGTH_BINS: usize = 10;
+
+/// Degree distribution. The histogram maps a degree to the number of vertices with it.
+#[derive(Debug, Clone, Serialize)]
+pub struct DegreeStats {
+    pub min: usize,
+    pub max: usize,
+    pub mean: f64,
+    pub histogram: BTreeMap<usize, usize>,
+}
+
+/// Distribution of vertex strengths, the weighted degrees, in equal width bins.
+#[derive(Debug, Clone, Serialize)]
+pub struct StrengthStats {
+    pub min: f64,
+    pub max: f64,
+    pub mean: f64,
+    pub median: f64,
+    pub histogram: Vec<HistogramBin>,
+}
+
+/// Distribution of edge weights, counting each undirected edge once.
+#[derive(Debug, Clone, Serialize)]
+pub struct WeightStats {
+    pub min: f64,
+    pub max: f64,
+    pub mean: f64,
+    pub std_dev: f64,
+    pub p25: f64,
+    pub median: f64,
+    pub p75: f64,
+}
+
+/// Shortest path lengths over all sources, or over `sources` sampled ones when not `exact`.
+#[derive(Debug, Clone, Serialize)]
+pub struct PathStats {
+    pub diameter: f32,
+    pub average_path_length: f64,
+    pub sources: usize,
+    pub exact: bool,
+}
+
+/// Summary report returned by `Graph::stats`. `Display` prints it as a table.
+#[derive(Debug, Clone, Serialize)]
+pub struct GraphStats {
+    pub vertex_count: usize,
+    pub edge_count: usize,
+    pub density: f64,
+    pub degree: DegreeStats,
+    pub strength: StrengthStats,
+    pub weight: WeightStats,
+    pub component_count: usize,
+    pub paths: PathStats,
+    pub triangle_count: usize,
+    pub clustering_coefficient: f64,
+    pub weighted_clustering_coefficient: f64,
+    pub transitivity: f64,
+    pub weighted_transitivity: f64,
+}
+
+impl GraphStats {
+    /// Pretty printed JSON form of the report.
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
+}
+
+impl Graph {
+    /// Computes the summary report, with exact path statistics for graphs of up to 1000
+    /// vertices and a fixed-seed sample of 100 sources beyond that.
+    pub fn stats(&self) -> GraphStats {
+        self.stats_observed(&Silent)
+    }
+
+    /// `stats` reporting the triangle pass behind the clustering measures, which takes
+    /// O(n^3) time on a complete graph, to `progress`. If `progress` cancels it, the
+    /// triangle and clustering figures only cover the triangles found so far.
+    pub fn stats_observed(&self, progress: &dyn Progress) -> GraphStats {
+        if self.vertices.len() <= EXACT_PATH_LIMIT {
+            self.compute_stats(None, progress)
+        } else {
+            self.compute_stats(Some((DEFAULT_PATH_SAMPLES, DEFAULT_SEED)), progress)
+        }
+    }
+
+    /// Computes the summary report with path statistics from `sources` random sources.
+    pub fn stats_sampled(&self, sources: usize, seed: u64) -> GraphStats {
+        self.compute_stats(Some((sources, seed)), &Silent)
+    }
+
+    fn compute_stats(&self, sample: Option<(usize, u64)>, progress: &dyn Progress) -> GraphStats {
+        let vertex_count = self.vertices.len();
+        let degrees: Vec<usize> = self.adjacency_list.iter().map(|adj_list| adj_list.len()).collect();
+        let edge_count = degrees.iter().sum::<usize>() / 2;
+        let density = if vertex_count > 1 {
+            2.0 * edge_count as f64 / (vertex_count as f64 * (vertex_count as f64 - 1.0))
+        } else {
+            0.0
+        };
+
+        let mut histogram = BTreeMap::new();
+        for &degree in &degrees {
+            *histogram.entry(degree).or_insert(0) += 1;
+        }
+        let degree = DegreeStats {
+            min: degrees.iter().copied().min().unwrap_or(0),
+            max: degrees.iter().copied().max().unwrap_or(0),
+            mean: if vertex_count > 0 { 2.0 * edge_count as f64 / vertex_count as f64 } else { 0.0 },
+            histogram,
+        };
+
+        let component_count = self.connected_components().into_iter().collect::<HashSet<_>>().len();
+        let clustering = self.clustering_observed(progress);
+
+        GraphStats {
+            vertex_count,
+            edge_count,
+            density,
+            degree,
+            strength: self.strength_distribution(STRENGTH_BINS),
+            weight: self.weight_stats(),
+            component_count,
+            paths: self.path_stats(sample),
+            triangle_count: clustering.triangle_count,
+            clustering_coefficient: clustering.average,
+            weighted_clustering_coefficient: clustering.weighted_average,
+            transitivity: clustering.transitivity,
+            weighted_transitivity: clustering.weighted_transitivity,
+        }
+    }
+
+    /// Distribution of vertex strengths over `bins` equal width bins.
+    pub fn strength_distribution(&self, bins: usize) -> StrengthStats {
+        let mut strengths: Vec<f64> = self.strengths().into_iter().map(|strength| strength as f64).collect();
+        strengths.sort_by(|a, b| a.total_cmp(b));
+        if strengths.is_empty() {
+            return StrengthStats { min: 0.0, max: 0.0, mean: 0.0, median: 0.0, histogram: vec![] };
+        }
+        StrengthStats {
+            min: strengths[0],
+            max: strengths[strengths.len() - 1],
+            mean: strengths.iter().sum::<f64>() / strengths.len() as f64,
+            median: quantile(&strengths, 0.5),
+            histogram: histogram(&strengths, bins),
+        }
+    }
+
+    fn weight_stats(&self) -> WeightStats {
+        let mut weights: Vec<f64> = vec![];
+        for (u, adj_list) in self.adjacency_list.iter().enumerate() {
+            for &(v, weight) in adj_list {
+                if u < v {
+                    weights.push(weight as f64);
+                }
+            }
+        }
+
+        if weights.is_empty() {
+            return WeightStats { min: 0.0, max: 0.0, mean: 0.0, std_dev: 0.0, p25: 0.0, median: 0.0, p75: 0.0 };
+        }
+
+        weights.sort_by(|a, b| a.total_cmp(b));
+        let count = weights.len() as f64;
+        let mean = weights.iter().sum::<f64>() / count;
+        let variance = weights.iter().map(|w| (w - mean).powi(2)).sum::<f64>() / count;
+
+        WeightStats {
+            min: weights[0],
+            max: weights[weights.len() - 1],
+            mean,
+            std_dev: variance.sqrt(),
+            p25: quantile(&weights, 0.25),
+            median: quantile(&weights, 0.5),
+            p75: quantile(&weights, 0.75),
+        }
+    }
+
+    fn path_stats(&self, sample: Option<(usize, u64)>) -> PathStats {
+        let vertex_count = self.vertices.len();
+        let sources: Vec<Vertex> = match sample {
+            Some((amount, seed)) if amount < vertex_count => {
+                let mut rng = StdRng::seed_from_u64(seed);
+                index::sample(&mut rng, vertex_count, amount).into_vec()
+            }
+            _ => (0..vertex_count).collect(),
+        };
+
+        let mut diameter: f32 = 0.0;
+        let mut total = 0.0;
+        let mut pairs = 0usize;
+        for &source in &sources {
+            for (target, distance) in self.dijkstra(source).into_iter().enumerate() {
+                if target != source && distance < f32::MAX {
+                    diameter = diameter.max(distance);
+                    total += distance as f64;
+                    pairs += 1;
+                }
+            }
+        }
+
+        PathStats {
+            diameter,
+            average_path_length: if pairs > 0 { total / pairs as f64 } else { 0.0 },
+            exact: sources.len() == vertex_count,
+            sources: sources.len(),
+        }
+    }
+
+}
+
+pub(crate) fn quantile(sorted: &[f64], q: f64) -> f64 {
+    let position = q * (sorted.len() - 1) as f64;
+    let lower = position.floor() as usize;
+    let upper = position.ceil() as usize;
+    sorted[lower] + (sorted[upper] - sorted[lower]) * (position - lower as f64)
+}
+
+impl fmt::Display for GraphStats {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let paths = if self.paths.exact { "exact".to_string() } else { format!("sampled, {} sources", self.paths.sources) };
+
+        writeln!(f, "{:<24} {}", "Vertices", self.vertex_count)?;
+        writeln!(f, "{:<24} {}", "Edges", self.edge_count)?;
+        writeln!(f, "{:<24} {:.4}", "Density", self.density)?;
+        writeln!(f, "{:<24} {}", "Components", self.component_count)?;
+        writeln!(f, "{:<24} min {} / max {} / mean {:.2}", "Degree", self.degree.min, self.degree.max, self.degree.mean)?;
+        writeln!(
+            f,
+            "{:<24} min {:.0} / median {:.0} / max {:.0} / mean {:.2}",
+            "Strength", self.strength.min, self.strength.median, self.strength.max, self.strength.mean
+        )?;
+        writeln!(
+            f,
+            "{:<24} min {:.0} / p25 {:.0} / median {:.0} / p75 {:.0} / max {:.0} / mean {:.2} / sd {:.2}",
+            "Weight", self.weight.min, self.weight.p25, self.weight.median, self.weight.p75, self.weight.max, self.weight.mean, self.weight.std_dev
+        )?;
+        writeln!(f, "{:<24} {:.2} ({})", "Diameter", self.paths.diameter, paths)?;
+        writeln!(f, "{:<24} {:.2} ({})", "Average path length", self.paths.average_path_length, paths)?;
+        writeln!(f, "{:<24} {}", "Triangles", self.triangle_count)?;
+        writeln!(f, "{:<24} {:.4} (weighted {:.4})", "Clustering coefficient", self.clustering_coefficient, self.weighted_clustering_coefficient)?;
+        writeln!(f, "{:<24} {:.4} (weighted {:.4})", "Transitivity", self.transitivity, self.weighted_transitivity)?;
+        writeln!(f, "Degree histogram:")?;
+        for (degree, count) in &self.degree.histogram {
+            writeln!(f, "  {:>8} {}", degree, count)?;
+        }
+        writeln!(f, "Strength histogram:")?;
+        for bin in &self.strength.histogram {
+            writeln!(f, "  {:>8.0} {}", bin.low, bin.count)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,245 @@
+use super::graph::Graph;
+use super::song::Song;
+use csv::{ReaderBuilder, Writer};
+use serde::Deserialize;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::error::Error;
+use std::fs::File;
+
+/// One song's row in the chart of a given day.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ChartEntry {
+    /// ISO date, `YYYY-MM-DD`.
+    pub date: String,
+    pub position: u32,
+    pub artist_name: String,
+    pub song_name: String,
+    pub streams: u64,
+}
+
+/// Reads dated chart rows from a CSV with `date,position,artist_name,song_name,streams` headers.
+pub fn load_chart_entries_from_csv(file_path: &str) -> Result<Vec<ChartEntry>, Box<dyn Error>> {
+    let file = File::open(file_path)?;
+    let mut rdr = ReaderBuilder::new().has_headers(true).from_reader(file);
+    let mut entries = vec![];
+
+    for result in rdr.deserialize() {
+        let entry: ChartEntry = result?;
+        entries.push(entry);
+    }
+
+    Ok(entries)
+}
+
+/// Length of the periods chart snapshots are grouped into.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Period {
+    Day,
+    /// Weeks start on Monday and are labelled by that Monday's date.
+    Week,
+    Month,
+    Year,
+}
+
+impl Period {
+    /// Label of the period containing `date`, e.g. `2021-03` for a month.
+    pub fn label(&self, date: &str) -> Result<String, Box<dyn Error>> {
+        let (year, month, day) = parse_date(date)?;
+        Ok(match self {
+            Period::Day => format!("{:04}-{:02}-{:02}", year, month, day),
+            Period::Week => {
+                let days = days_from_civil(year, month, day);
+                // 1970-01-01 was a Thursday, three days after a Monday
+                let monday = days - (days + 3).rem_euclid(7);
+                let (year, month, day) = civil_from_days(monday);
+                format!("{:04}-{:02}-{:02}", year, month, day)
+            }
+            Period::Month => format!("{:04}-{:02}", year, month),
+            Period::Year => format!("{:04}", year),
+        })
+    }
+}
+
+impl std::str::FromStr for Period {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        match text {
+            "day" => Ok(Period::Day),
+            "week" => Ok(Period::Week),
+            "month" => Ok(Period::Month),
+            "year" => Ok(Period::Year),
+            _ => Err(format!("unknown period `{}`, expected day, week, month or year", text)),
+        }
+    }
+}
+
+fn parse_date(date: &str) -> Result<(i64, u32, u32), Box<dyn Error>> {
+    let invalid = || format!("invalid date `{}`, expected YYYY-MM-DD", date);
+    let mut parts = date.trim().splitn(3, '-');
+    let mut next = || parts.next().and_then(|part| part.parse::<i64>().ok()).ok_or_else(invalid);
+    let (year, month, day) = (next()?, next()?, next()?);
+    let days_in_month = match month {
+        1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+        4 | 6 | 9 | 11 => 30,
+        2 if (year % 4 == 0 && year % 100 != 0) || year % 400 == 0 => 29,
+        2 => 28,
+        _ => return Err(invalid().into()),
+    };
+    if day < 1 || day > days_in_month {
+        return Err(invalid().into());
+    }
+    Ok((year, month as u32, day as u32))
+}
+
+// Days since 1970-01-01 in the proleptic Gregorian calendar (Howard Hinnant's algorithm).
+fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let month = month as i64;
+    let day_of_year = (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + day as i64 - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146097 + day_of_era - 719468
+}
+
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let days = days + 719468;
+    let era = days.div_euclid(146097);
+    let day_of_era = days - era * 146097;
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * month_index + 2) / 5 + 1) as u32;
+    let month = if month_index < 10 { month_index + 3 } else { month_index - 9 } as u32;
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
+
+/// The songs that charted within one period, aggregated from its snapshots.
+#[derive(Debug, Clone)]
+pub struct ChartPeriod {
+    pub label: String,
+    pub songs: Vec<Song>,
+}
+
+/// Groups chart entries into periods, oldest first. Within a period each song becomes one
+/// `Song`: `days` counts the distinct dates it charted, `peak_position` is its best position
+/// and `total_streams` sums its streams.
+pub fn songs_by_period(entries: &[ChartEntry], period: Period) -> Result<Vec<ChartPeriod>, Box<dyn Error>> {
+    let mut periods: BTreeMap<String, Vec<(Song, BTreeSet<&str>)>> = BTreeMap::new();
+    let mut indices: HashMap<(String, &str, &str), usize> = HashMap::new();
+
+    for entry in entries {
+        let label = period.label(&entry.date)?;
+        let key = (label.clone(), entry.artist_name.trim(), entry.song_name.trim());
+        let songs = periods.entry(label).or_default();
+        let index = *indices.entry(key).or_insert_with(|| {
+            songs.push((
+                Song {
+                    artist_name: entry.artist_name.trim().to_string(),
+                    song_name: entry.song_name.trim().to_string(),
+                    days: 0,
+                    peak_position: entry.position,
+                    total_streams: 0,
+                },
+                BTreeSet::new(),
+            ));
+            songs.len() - 1
+        });
+
+        let (song, dates) = &mut songs[index];
+        dates.insert(entry.date.trim());
+        song.days = dates.len() as u32;
+        song.peak_position = song.peak_position.min(entry.position);
+        song.total_streams += entry.streams;
+    }
+
+    Ok(periods
+        .into_iter()
+        .map(|(label, songs)| ChartPeriod { label, songs: songs.into_iter().map(|(song, _)| song).collect() })
+        .collect())
+}
+
+/// One song's closeness in every period, `None` where it did not chart.
+#[derive(Debug, Clone)]
+pub struct SongTimeline {
+    pub artist_name: String,
+    pub song_name: String,
+    pub scores: Vec<Option<f32>>,
+}
+
+impl SongTimeline {
+    /// Score in the last period it charted minus the score in the first.
+    pub fn net_change(&self) -> f32 {
+        let mut present = self.scores.iter().flatten();
+        match (present.next(), present.last()) {
+            (Some(first), Some(last)) => last - first,
+            _ => 0.0,
+        }
+    }
+}
+
+/// Closeness centrality of every song across a sequence of periods.
+#[derive(Debug, Clone)]
+pub struct CentralityTimeline {
+    pub periods: Vec<String>,
+    pub songs: Vec<SongTimeline>,
+}
+
+impl CentralityTimeline {
+    /// Builds one song graph per period and records each song's closeness in it.
+    pub fn build(entries: &[ChartEntry], period: Period) -> Result<Self, Box<dyn Error>> {
+        let periods = songs_by_period(entries, period)?;
+        let labels: Vec<String> = periods.iter().map(|p| p.label.clone()).collect();
+        let mut songs: Vec<SongTimeline> = vec![];
+        let mut indices: HashMap<(String, String), usize> = HashMap::new();
+
+        for (column, chart_period) in periods.into_iter().enumerate() {
+            let graph = Graph::build_from_songs(chart_period.songs);
+            for (vertex, (song_name, artist_name, closeness)) in graph.closeness_centrality() {
+                let song = &graph.vertices[vertex];
+                let index = *indices.entry((song.artist_name.clone(), song.song_name.clone())).or_insert_with(|| {
+                    songs.push(SongTimeline { artist_name, song_name, scores: vec![None; labels.len()] });
+                    songs.len() - 1
+                });
+                songs[index].scores[column] = Some(closeness);
+            }
+        }
+
+        songs.sort_by(|a, b| (&a.artist_name, &a.song_name).cmp(&(&b.artist_name, &b.song_name)));
+        Ok(CentralityTimeline { periods: labels, songs })
+    }
+
+    /// The `n` songs whose closeness grew the most between their first and last period.
+    pub fn rising(&self, n: usize) -> Vec<&SongTimeline> {
+        let mut songs: Vec<&SongTimeline> = self.songs.iter().filter(|song| song.net_change() > 0.0).collect();
+        songs.sort_by(|a, b| b.net_change().total_cmp(&a.net_change()));
+        songs.truncate(n);
+        songs
+    }
+
+    /// The `n` songs whose closeness shrank the most between their first and last period.
+    pub fn falling(&self, n: usize) -> Vec<&SongTimeline> {
+        let mut songs: Vec<&SongTimeline> = self.songs.iter().filter(|song| song.net_change() < 0.0).collect();
+        songs.sort_by(|a, b| a.net_change().total_cmp(&b.net_change()));
+        songs.truncate(n);
+        songs
+    }
+
+    /// Writes one row per song with a column per period; periods it did not chart in are empty.
+    pub fn write_csv(&self, file_path: &str) -> Result<(), Box<dyn Error>> {
+        let mut writer = Writer::from_path(file_path)?;
+        let mut header = vec!["artist_name".to_string(), "song_name".to_string()];
+        header.extend(self.periods.iter().cloned());
+        writer.write_record(&header)?;
+
+        for song in &self.songs {
+            let mut row = vec![song.artist_name.clone(), song.song_name.clone()];
+            row.extend(song.scores.iter().map(|score| score.map_or(String::new(), |score| format!("{:.4}", score))));
+            writer.write_record(&row)?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+}
@@ -0,0 +1,232 @@
+use super::graph::{Graph, Weight};
+use super::progress::{Progress, Silent};
+use super::song::Song;
+use super::stats::quantile;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+const QUANTILES: [f64; 9] = [0.01, 0.05, 0.1, 0.25, 0.5, 0.75, 0.9, 0.95, 0.99];
+
+/// Coefficients of the edge weight formula
+/// `scale / (1 + peak_position * Δpeak + total_streams * Δstreams / 10⁶ + days * Δdays / 10)`,
+/// where the deltas are absolute differences between two songs. The default is the formula
+/// the graph has always used.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct WeightParams {
+    pub scale: f32,
+    pub peak_position: f32,
+    /// Per million streams.
+    pub total_streams: f32,
+    /// Per ten days on the chart.
+    pub days: f32,
+}
+
+impl Default for WeightParams {
+    fn default() -> Self {
+        WeightParams { scale: 1000.0, peak_position: 0.05, total_streams: 0.01, days: 0.1 }
+    }
+}
+
+impl WeightParams {
+    /// Weight of the edge between two songs under these coefficients.
+    pub fn weight(&self, song_a: &Song, song_b: &Song) -> Weight {
+        let peak_position_diff = (song_a.peak_position as isize - song_b.peak_position as isize).abs();
+        let stream_diff = (song_a.total_streams as isize - song_b.total_streams as isize).abs();
+        let days_diff = (song_a.days as isize - song_b.days as isize).abs();
+
+        let weight = self.scale
+            / (1.0 + self.peak_position * peak_position_diff as f32 + self.total_streams * stream_diff as f32 / 1000000.0 + self.days * days_diff as f32 / 10.0);
+        weight as isize
+    }
+}
+
+/// Similarity weight `add_weighted_edge_by_features` gives the edge between two songs. It is
+/// 1000 for identical chart runs and shrinks as peak position, streams and days drift apart.
+pub fn feature_weight(song_a: &Song, song_b: &Song) -> Weight {
+    WeightParams::default().weight(song_a, song_b)
+}
+
+/// Number of values, such as pair weights, falling in `[low, high)`; the last bin also holds `high`.
+#[derive(Debug, Clone, Serialize)]
+pub struct HistogramBin {
+    pub low: f64,
+    pub high: f64,
+    pub count: usize,
+}
+
+/// Distribution of the weights the complete graph over some songs would have, measured on
+/// every pair or on a seeded sample of them. `Display` prints it as a table.
+#[derive(Debug, Clone, Serialize)]
+pub struct WeightDistribution {
+    pub song_count: usize,
+    pub pair_count: usize,
+    pub sampled_pairs: usize,
+    pub exact: bool,
+    pub min: f64,
+    pub max: f64,
+    pub mean: f64,
+    /// `(q, weight)` for the 1st, 5th, 10th, 25th, 50th, 75th, 90th, 95th and 99th percentiles.
+    pub quantiles: Vec<(f64, f64)>,
+    pub histogram: Vec<HistogramBin>,
+    #[serde(skip)]
+    sorted: Vec<f64>,
+}
+
+/// Measures the weights between `songs`, using every pair when there are at most `max_pairs`
+/// of them and `max_pairs` pairs drawn with `seed` otherwise. The histogram has `bins` bins.
+pub fn weight_distribution(songs: &[Song], max_pairs: usize, bins: usize, seed: u64) -> WeightDistribution {
+    let n = songs.len();
+    let pair_count = n * n.saturating_sub(1) / 2;
+    let exact = pair_count <= max_pairs;
+
+    let mut weights: Vec<f64> = Vec::with_capacity(pair_count.min(max_pairs));
+    if exact {
+        for i in 0..n {
+            for j in (i + 1)..n {
+                weights.push(feature_weight(&songs[i], &songs[j]) as f64);
+            }
+        }
+    } else {
+        let mut rng = StdRng::seed_from_u64(seed);
+        while weights.len() < max_pairs {
+            let i = rng.gen_range(0..n);
+            let j = rng.gen_range(0..n);
+            if i != j {
+                weights.push(feature_weight(&songs[i], &songs[j]) as f64);
+            }
+        }
+    }
+    weights.sort_by(|a, b| a.total_cmp(b));
+
+    let (min, max) = (weights.first().copied().unwrap_or(0.0), weights.last().copied().unwrap_or(0.0));
+    let mean = if weights.is_empty() { 0.0 } else { weights.iter().sum::<f64>() / weights.len() as f64 };
+    let quantiles = if weights.is_empty() { vec![] } else { QUANTILES.iter().map(|&q| (q, quantile(&weights, q))).collect() };
+
+    let histogram = histogram(&weights, bins);
+
+    WeightDistribution {
+        song_count: n,
+        pair_count,
+        sampled_pairs: weights.len(),
+        exact,
+        min,
+        max,
+        mean,
+        quantiles,
+        histogram,
+        sorted: weights,
+    }
+}
+
+// `bins` equal width bins from the smallest to the largest of the `sorted` values, none if empty.
+pub(crate) fn histogram(sorted: &[f64], bins: usize) -> Vec<HistogramBin> {
+    let (min, max) = (sorted.first().copied().unwrap_or(0.0), sorted.last().copied().unwrap_or(0.0));
+    let bins = if sorted.is_empty() { 0 } else { bins.max(1) };
+    let width = (max - min) / bins.max(1) as f64;
+    let mut histogram: Vec<HistogramBin> = (0..bins)
+        .map(|bin| HistogramBin { low: min + width * bin as f64, high: min + width * (bin + 1) as f64, count: 0 })
+        .collect();
+    for &value in sorted {
+        let bin = if width > 0.0 { (((value - min) / width) as usize).min(bins - 1) } else { 0 };
+        histogram[bin].count += 1;
+    }
+    histogram
+}
+
+impl WeightDistribution {
+    /// Smallest weight to keep so that the pruned graph has an average degree of roughly
+    /// `degree`, estimated from the measured pairs. Ties at the threshold are all kept, so the
+    /// real degree can come out higher.
+    pub fn threshold_for_average_degree(&self, degree: f64) -> Weight {
+        if self.sorted.is_empty() {
+            return 0;
+        }
+        let kept = (degree / self.song_count.saturating_sub(1).max(1) as f64).clamp(0.0, 1.0);
+        let index = (((1.0 - kept) * self.sorted.len() as f64) as usize).min(self.sorted.len() - 1);
+        self.sorted[index] as Weight
+    }
+
+    /// Pretty printed JSON form of the report.
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
+}
+
+/// The largest weight threshold that keeps the pruned graph over `songs` connected: the
+/// lightest edge of a maximum spanning tree of the complete graph. Runs Prim's algorithm on
+/// the implicit graph in O(n^2) time without building it. `None` for fewer than two songs.
+pub fn connectivity_threshold(songs: &[Song]) -> Option<Weight> {
+    let n = songs.len();
+    if n < 2 {
+        return None;
+    }
+
+    let mut in_tree = vec![false; n];
+    let mut best: Vec<Weight> = vec![Weight::MIN; n];
+    let mut lightest = Weight::MAX;
+    let mut current = 0;
+    in_tree[0] = true;
+
+    for _ in 1..n {
+        let mut next = None;
+        for v in 0..n {
+            if in_tree[v] {
+                continue;
+            }
+            best[v] = best[v].max(feature_weight(&songs[current], &songs[v]));
+            if next.is_none_or(|u: usize| best[v] > best[u]) {
+                next = Some(v);
+            }
+        }
+        let v = next?;
+        lightest = lightest.min(best[v]);
+        in_tree[v] = true;
+        current = v;
+    }
+    Some(lightest)
+}
+
+impl Graph {
+    /// Like `build_from_songs`, but only connects pairs whose weight is at least `min_weight`.
+    pub fn build_from_songs_with_threshold(songs: Vec<Song>, min_weight: Weight) -> Self {
+        Self::build_with_weights(songs, &WeightParams::default(), Some(min_weight))
+    }
+
+    /// Builds the song graph with edges weighted by `params`, connecting every pair or, with
+    /// `min_weight`, only the pairs whose weight reaches it.
+    pub fn build_with_weights(songs: Vec<Song>, params: &WeightParams, min_weight: Option<Weight>) -> Self {
+        Self::build_with_weights_observed(songs, params, min_weight, &Silent)
+    }
+
+    /// `build_with_weights` reporting one step per pair to `progress`. If `progress` cancels
+    /// the build, the graph holds every song but only the edges added so far.
+    pub fn build_with_weights_observed(songs: Vec<Song>, params: &WeightParams, min_weight: Option<Weight>, progress: &dyn Progress) -> Self {
+        let mut graph = Self::new();
+        for song in songs {
+            graph.add_vertex(song);
+        }
+        graph.connect_pairs(|a, b| params.weight(a, b), min_weight, progress);
+        graph
+    }
+}
+
+impl fmt::Display for WeightDistribution {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let pairs = if self.exact { "all pairs".to_string() } else { format!("sampled {} of {} pairs", self.sampled_pairs, self.pair_count) };
+
+        writeln!(f, "{:<24} {}", "Songs", self.song_count)?;
+        writeln!(f, "{:<24} {}", "Pairs", pairs)?;
+        writeln!(f, "{:<24} min {:.0} / max {:.0} / mean {:.2}", "Weight", self.min, self.max, self.mean)?;
+        writeln!(f, "Quantiles:")?;
+        for (q, weight) in &self.quantiles {
+            writeln!(f, "  {:>8} {:.0}", format!("p{:.0}", q * 100.0), weight)?;
+        }
+        writeln!(f, "Histogram:")?;
+        for bin in &self.histogram {
+            writeln!(f, "  {:>6.0} - {:<6.0} {}", bin.low, bin.high, bin.count)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,36 @@
+mod common;
+
+use common::song;
+use spotify_songs_graph_analysis::artist::WeightAggregation;
+use spotify_songs_graph_analysis::Graph;
+
+#[test]
+fn test_artist_graph_aggregation() {
+    let mut graph = Graph::new();
+    graph.add_vertex(song("A ", "a1", 10, 1, 500));
+    graph.add_vertex(song("A", "a2", 20, 3, 900));
+    graph.add_vertex(song("B", "b1", 5, 2, 100));
+    graph.add_vertex(song("B", "b2", 5, 8, 100));
+    graph.add_edge(0, 1, 999);
+    graph.add_edge(0, 2, 10);
+    graph.add_edge(0, 3, 20);
+    graph.add_edge(1, 2, 30);
+    graph.add_edge(1, 3, 41);
+
+    let mean = graph.build_artist_graph(WeightAggregation::Mean);
+    assert_eq!(mean.vertices.len(), 2);
+    assert_eq!(mean.vertices[0].artist_name, "A");
+    assert_eq!(mean.vertices[0].song_name, "a2");
+    assert_eq!(mean.vertices[0].days, 30);
+    assert_eq!(mean.vertices[0].peak_position, 1);
+    assert_eq!(mean.vertices[0].total_streams, 1400);
+    assert_eq!(mean.adjacency_list[0], vec![(1, 25)]);
+    assert_eq!(mean.adjacency_list[1], vec![(0, 25)]);
+
+    let max = graph.build_artist_graph(WeightAggregation::Max);
+    assert_eq!(max.adjacency_list[0], vec![(1, 41)]);
+
+    let sum = graph.build_artist_graph(WeightAggregation::Sum);
+    assert_eq!(sum.adjacency_list[0], vec![(1, 101)]);
+    assert!(sum.closeness_centrality().values().all(|&(_, _, closeness)| closeness > 0.0));
+}
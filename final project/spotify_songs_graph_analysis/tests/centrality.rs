@@ -0,0 +1,55 @@
+mod common;
+
+use common::{detour_fixture, path_fixture};
+use spotify_songs_graph_analysis::centrality::Centrality;
+use spotify_songs_graph_analysis::generators::{Generator, WeightSpec};
+
+fn assert_close(actual: f32, expected: f32) {
+    assert!((actual - expected).abs() < 1e-5, "expected {}, got {}", expected, actual);
+}
+
+#[test]
+fn test_harmonic_centrality() {
+    let scores = path_fixture().harmonic_centrality();
+    assert_close(scores[&0].2, (1.0 + 1.0 / 3.0 + 1.0 / 6.0) / 3.0);
+    assert_close(scores[&3].2, (1.0 / 3.0 + 1.0 / 5.0 + 1.0 / 6.0) / 3.0);
+
+    // the isolated vertex contributes nothing and scores nothing
+    let scores = detour_fixture().harmonic_centrality();
+    assert_close(scores[&0].2, (1.0 + 0.5) / 3.0);
+    assert_close(scores[&1].2, 2.0 / 3.0);
+    assert_close(scores[&3].2, 0.0);
+}
+
+#[test]
+fn test_wasserman_faust_closeness() {
+    // on a connected graph it is (n - 1) over the sum of distances
+    let scores = path_fixture().wasserman_faust_closeness();
+    assert_close(scores[&0].2, 3.0 / 10.0);
+    assert_close(scores[&1].2, 3.0 / 8.0);
+
+    let scores = detour_fixture().wasserman_faust_closeness();
+    assert_close(scores[&0].2, 2.0 / 3.0 * 2.0 / 3.0);
+    assert_close(scores[&1].2, 2.0 / 3.0);
+    assert_close(scores[&3].2, 0.0);
+    // the original closeness rates the isolated vertex as infinitely central
+    assert!(detour_fixture().closeness_centrality()[&3].2.is_infinite());
+}
+
+#[test]
+fn test_normalised_scores_do_not_depend_on_size() {
+    for n in [5, 20, 80] {
+        let graph = Generator::new(1, WeightSpec::Constant(1)).complete(n);
+        for metric in [Centrality::Harmonic, Centrality::WassermanFaust] {
+            assert!(graph.centrality(metric).values().all(|&(_, _, score)| (score - 1.0).abs() < 1e-5));
+        }
+    }
+}
+
+#[test]
+fn test_metric_names_round_trip() {
+    for metric in Centrality::ALL {
+        assert_eq!(metric.name().parse::<Centrality>(), Ok(metric));
+    }
+    assert!("betweenness".parse::<Centrality>().is_err());
+}
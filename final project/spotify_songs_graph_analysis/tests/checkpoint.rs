@@ -0,0 +1,103 @@
+mod common;
+
+use common::{dataset, temp_path, SEED};
+use spotify_songs_graph_analysis::centrality::Centrality;
+use spotify_songs_graph_analysis::checkpoint::Checkpoint;
+use spotify_songs_graph_analysis::progress::{Progress, Silent};
+use spotify_songs_graph_analysis::sample::uniform_subset;
+use spotify_songs_graph_analysis::Graph;
+use std::cell::Cell;
+
+// Cancels once `limit` sources are scored, like a run killed part way.
+struct StopAfter {
+    limit: usize,
+    done: Cell<usize>,
+}
+
+impl Progress for StopAfter {
+    fn advance(&self, done: usize) {
+        self.done.set(done);
+    }
+
+    fn is_cancelled(&self) -> bool {
+        self.done.get() >= self.limit
+    }
+}
+
+fn checkpoint_path(name: &str) -> std::path::PathBuf {
+    let path = temp_path(name);
+    let _ = std::fs::remove_file(&path);
+    path
+}
+
+#[test]
+fn test_fingerprint() {
+    let songs = uniform_subset(dataset(), 20, SEED);
+    let graph = Graph::build_from_songs(songs.clone());
+    assert_eq!(graph.fingerprint(), Graph::build_from_songs(songs.clone()).fingerprint());
+
+    let mut reweighted = Graph::build_from_songs(songs.clone());
+    reweighted.adjacency_list[3][0].1 += 1;
+    assert_ne!(graph.fingerprint(), reweighted.fingerprint());
+
+    let mut renamed = songs;
+    renamed[0].song_name.push('!');
+    assert_ne!(graph.fingerprint(), Graph::build_from_songs(renamed).fingerprint());
+}
+
+#[test]
+fn test_resume_from_checkpoint() {
+    let graph = Graph::build_from_songs(uniform_subset(dataset(), 25, SEED));
+    let path = checkpoint_path("checkpoint_test_resume.txt");
+
+    let stop = StopAfter { limit: 7, done: Cell::new(0) };
+    let partial = graph.centrality_checkpointed(Centrality::Harmonic, &path, 3, &stop).unwrap();
+    assert_eq!(partial.len(), 7);
+    let saved = Checkpoint::read(&path).unwrap();
+    assert_eq!((saved.fingerprint, saved.metric, saved.vertex_count), (graph.fingerprint(), Centrality::Harmonic, 25));
+    assert_eq!(saved.scores.len(), 7);
+
+    // the resumed run only computes the other sources and matches an uninterrupted one
+    let resumed = StopAfter { limit: usize::MAX, done: Cell::new(0) };
+    let scores = graph.centrality_checkpointed(Centrality::Harmonic, &path, 3, &resumed).unwrap();
+    assert_eq!(scores, graph.harmonic_centrality());
+    assert_eq!(Checkpoint::read(&path).unwrap().scores.len(), 25);
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn test_checkpoint_of_another_graph_is_rejected() {
+    let songs = uniform_subset(dataset(), 15, SEED);
+    let graph = Graph::build_from_songs(songs[..14].to_vec());
+    let path = checkpoint_path("checkpoint_test_mismatch.txt");
+    graph.centrality_checkpointed(Centrality::Closeness, &path, 5, &Silent).unwrap();
+
+    let other = Graph::build_from_songs(songs);
+    let error = other.centrality_checkpointed(Centrality::Closeness, &path, 5, &Silent).unwrap_err();
+    assert!(error.to_string().contains("different graph"), "{}", error);
+    let error = graph.centrality_checkpointed(Centrality::Harmonic, &path, 5, &Silent).unwrap_err();
+    assert!(error.to_string().contains("holds closeness scores"), "{}", error);
+
+    // scores survive the text format exactly, infinities included
+    let mut checkpoint = Checkpoint::read(&path).unwrap();
+    checkpoint.scores.insert(0, f32::INFINITY);
+    checkpoint.write(&path).unwrap();
+    assert_eq!(Checkpoint::read(&path).unwrap(), checkpoint);
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn test_write_error_still_finishes_progress() {
+    struct Finished(Cell<bool>);
+    impl Progress for Finished {
+        fn finish(&self) {
+            self.0.set(true);
+        }
+    }
+
+    let graph = Graph::build_from_songs(uniform_subset(dataset(), 5, SEED));
+    let progress = Finished(Cell::new(false));
+    let path = temp_path("missing_directory").join("checkpoint.txt");
+    assert!(graph.centrality_checkpointed(Centrality::Closeness, &path, 2, &progress).is_err());
+    assert!(progress.0.get());
+}
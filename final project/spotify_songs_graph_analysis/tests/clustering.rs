@@ -0,0 +1,33 @@
+mod common;
+
+use common::graph_with_vertices;
+
+#[test]
+fn test_triangles_and_clustering() {
+    let mut graph = graph_with_vertices(5);
+    graph.add_edge(0, 1, 4);
+    graph.add_edge(0, 2, 2);
+    graph.add_edge(0, 3, 2);
+    graph.add_edge(1, 2, 2);
+    graph.add_edge(1, 3, 2);
+    graph.add_edge(3, 4, 2);
+    // a repeated edge must not create extra triangles
+    graph.add_edge(1, 3, 2);
+
+    assert_eq!(graph.triangle_count(), 2);
+    assert_eq!(graph.triangles_per_vertex(), vec![2, 2, 1, 1, 0]);
+
+    let local = graph.local_clustering();
+    let expected = [2.0 / 3.0, 2.0 / 3.0, 1.0, 1.0 / 3.0, 0.0];
+    for (value, expected) in local.iter().zip(expected) {
+        assert!((value - expected).abs() < 1e-9);
+    }
+    assert!((graph.average_clustering() - expected.iter().sum::<f64>() / 5.0).abs() < 1e-9);
+    assert!((graph.global_clustering() - 0.6).abs() < 1e-9);
+
+    let weighted = graph.weighted_local_clustering();
+    assert!((weighted[2] - 0.25f64.cbrt()).abs() < 1e-9);
+    assert_eq!(weighted[4], 0.0);
+    // closed triples are worth 8 per triangle, all triples 8 + 8 + 2 + 6
+    assert!((graph.weighted_global_clustering() - 16.0 / 24.0).abs() < 1e-9);
+}
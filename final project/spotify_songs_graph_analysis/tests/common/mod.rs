@@ -0,0 +1,54 @@
+#![allow(dead_code)]
+
+use spotify_songs_graph_analysis::{load_songs_from_csv, Graph, Song};
+use std::path::PathBuf;
+use std::sync::OnceLock;
+
+pub const SEED: u64 = 210;
+
+pub fn dataset() -> &'static [Song] {
+    static SONGS: OnceLock<Vec<Song>> = OnceLock::new();
+    SONGS.get_or_init(|| load_songs_from_csv(concat!(env!("CARGO_MANIFEST_DIR"), "/Spotify_final_dataset.csv")).unwrap())
+}
+
+/// Path in the temp directory named after this test process, so concurrent test runs and
+/// tests writing different files never share one.
+pub fn temp_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("song-graph-{}-{}", std::process::id(), name))
+}
+
+pub fn song(artist_name: &str, song_name: &str, days: u32, peak_position: u32, total_streams: u64) -> Song {
+    Song {
+        artist_name: artist_name.to_string(),
+        song_name: song_name.to_string(),
+        days,
+        peak_position,
+        total_streams,
+    }
+}
+
+pub fn graph_with_vertices(count: usize) -> Graph {
+    let mut graph = Graph::new();
+    for i in 0..count {
+        graph.add_vertex(song("X", &format!("song {}", i), 1, 1, 1));
+    }
+    graph
+}
+
+// 0 -1- 1 -2- 2 -3- 3
+pub fn path_fixture() -> Graph {
+    let mut graph = graph_with_vertices(4);
+    graph.add_edge(0, 1, 1);
+    graph.add_edge(1, 2, 2);
+    graph.add_edge(2, 3, 3);
+    graph
+}
+
+// a triangle whose direct 0-2 edge is longer than the detour through 1, plus an isolated vertex
+pub fn detour_fixture() -> Graph {
+    let mut graph = graph_with_vertices(4);
+    graph.add_edge(0, 1, 1);
+    graph.add_edge(1, 2, 1);
+    graph.add_edge(0, 2, 5);
+    graph
+}
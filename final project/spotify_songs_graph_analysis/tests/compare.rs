@@ -0,0 +1,62 @@
+mod common;
+
+use common::{path_fixture, song, temp_path};
+use spotify_songs_graph_analysis::centrality::Centrality;
+use spotify_songs_graph_analysis::compare::{fractional_ranks, kendall_tau_b, spearman, STREAMS};
+use spotify_songs_graph_analysis::Graph;
+
+fn assert_close(actual: f64, expected: f64) {
+    assert!((actual - expected).abs() < 1e-9, "expected {}, got {}", expected, actual);
+}
+
+#[test]
+fn test_fractional_ranks_average_ties() {
+    assert_eq!(fractional_ranks(&[1.0, 3.0, 3.0, f32::NAN, 0.5]), vec![3.0, 1.5, 1.5, 5.0, 4.0]);
+}
+
+#[test]
+fn test_rank_correlations() {
+    let a = [1.0, 2.0, 3.0, 4.0];
+    assert_close(spearman(&a, &a), 1.0);
+    assert_close(kendall_tau_b(&a, &[4.0, 3.0, 2.0, 1.0]), -1.0);
+    // one swapped pair out of six
+    assert_close(kendall_tau_b(&a, &[2.0, 1.0, 3.0, 4.0]), 4.0 / 6.0);
+    assert_close(spearman(&a, &[2.0, 1.0, 3.0, 4.0]), 0.8);
+    // tau-b with a tie: 5 concordant, 1 tied in the second ranking, 6 pairs
+    assert_close(kendall_tau_b(&a, &[1.0, 2.0, 3.5, 3.5]), 5.0 / (6.0f64 * 5.0).sqrt());
+    assert_close(spearman(&a, &[1.0; 4]), 0.0);
+}
+
+#[test]
+fn test_compare_centrality() {
+    let mut graph = path_fixture();
+    for (v, streams) in [40, 30, 20, 10].into_iter().enumerate() {
+        graph.vertices[v].total_streams = streams;
+    }
+
+    let comparison = graph.compare_centrality(&Centrality::ALL);
+    assert_eq!(comparison.rows.len(), 4);
+    assert_eq!(comparison.correlations.len(), 6);
+    assert_eq!(comparison.rows[0].stream_rank, 1.0);
+    // on a connected graph Wasserman-Faust orders vertices exactly like closeness
+    assert_close(comparison.correlation("closeness", "wasserman-faust").unwrap().spearman, 1.0);
+    assert!(comparison.correlation(STREAMS, "harmonic").is_some());
+
+    // vertex 0 is the most streamed but only third by closeness
+    let divergent = comparison.divergent(Centrality::Closeness, 1);
+    assert_eq!(divergent[0].vertex, 0);
+    assert_eq!(divergent[0].ranks[0], 3.0);
+}
+
+#[test]
+fn test_comparison_csv() {
+    let graph = Graph::build_from_songs(vec![song("A", "a", 1, 1, 10), song("B", "b", 5, 3, 20), song("C", "c", 9, 9, 30)]);
+    let path = temp_path("compare_centrality.csv");
+    graph.compare_centrality(&[Centrality::Harmonic]).write_csv(path.to_str().unwrap()).unwrap();
+
+    let text = std::fs::read_to_string(&path).unwrap();
+    let mut lines = text.lines();
+    assert_eq!(lines.next(), Some("artist_name,song_name,total_streams,stream_rank,harmonic,harmonic_rank"));
+    assert_eq!(lines.count(), 3);
+    std::fs::remove_file(path).unwrap();
+}
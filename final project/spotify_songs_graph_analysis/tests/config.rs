@@ -0,0 +1,122 @@
+mod common;
+
+use common::{dataset, song, temp_path, SEED};
+use spotify_songs_graph_analysis::centrality::Centrality;
+use spotify_songs_graph_analysis::config::{ConfigError, RunConfig};
+use spotify_songs_graph_analysis::progress::Progress;
+use spotify_songs_graph_analysis::sample::uniform_subset;
+use spotify_songs_graph_analysis::weights::{feature_weight, WeightParams};
+use spotify_songs_graph_analysis::Graph;
+use std::cell::{Cell, RefCell};
+
+// Cancels once `after` steps of `task` are done, leaving every other task alone.
+struct CancelDuring {
+    task: &'static str,
+    after: usize,
+    current: RefCell<String>,
+    done: Cell<usize>,
+}
+
+impl Progress for CancelDuring {
+    fn start(&self, task: &str, _total: usize) {
+        *self.current.borrow_mut() = task.to_string();
+        self.done.set(0);
+    }
+
+    fn advance(&self, done: usize) {
+        self.done.set(done);
+    }
+
+    fn is_cancelled(&self) -> bool {
+        *self.current.borrow() == self.task && self.done.get() >= self.after
+    }
+}
+
+fn error_key(text: &str) -> Option<String> {
+    RunConfig::from_toml_str(text).unwrap_err().key
+}
+
+#[test]
+fn test_example_config_and_defaults() {
+    let example = RunConfig::from_file(concat!(env!("CARGO_MANIFEST_DIR"), "/analysis.toml")).unwrap();
+    assert_eq!(example.metrics().unwrap(), Centrality::ALL.to_vec());
+    assert_eq!(example.weights, WeightParams::default());
+
+    let empty = RunConfig::from_toml_str("").unwrap();
+    assert_eq!(empty.input.path, "Spotify_final_dataset.csv");
+    assert_eq!(empty.graph.kind, "complete");
+    assert_eq!(empty.metrics().unwrap(), vec![Centrality::Closeness]);
+
+    // the default coefficients are the original formula
+    let (a, b) = (song("A", "a", 10, 4, 20_000_000), song("B", "b", 250, 60, 310_000_000));
+    assert_eq!(WeightParams::default().weight(&a, &b), feature_weight(&a, &b));
+    let heavier = WeightParams { peak_position: 0.5, ..WeightParams::default() };
+    assert!(heavier.weight(&a, &b) < feature_weight(&a, &b));
+}
+
+#[test]
+fn test_errors_name_the_key() {
+    assert_eq!(error_key("[filters]\nmin_days = 50\nmax_days = 10").as_deref(), Some("filters.min_days"));
+    assert_eq!(error_key("[sample]\nsize = 0").as_deref(), Some("sample.size"));
+    assert_eq!(error_key("[weights]\ndays = -1.0").as_deref(), Some("weights.days"));
+    assert_eq!(error_key("[graph]\nkind = \"knn\"").as_deref(), Some("graph.k"));
+    assert_eq!(error_key("[graph]\nkind = \"star\"").as_deref(), Some("graph.kind"));
+    assert_eq!(error_key("[analysis]\nmetrics = [\"closeness\", \"pagerank\"]").as_deref(), Some("analysis.metrics[1]"));
+    assert_eq!(error_key("[input]\ncolumns = { artist = \"Artist\" }").as_deref(), Some("input.columns.artist"));
+    assert_eq!(error_key("[input]\npath = \"songs.csv\"\nformat = \"xml\"").as_deref(), Some("input.format"));
+    assert_eq!(error_key("[output]\ncompare_csv = \"out.csv\"").as_deref(), Some("output.compare_csv"));
+
+    // unknown keys and wrong types come from the parser, which quotes the offending line
+    let unknown = RunConfig::from_toml_str("[filters]\nmin_dayz = 5").unwrap_err();
+    assert!(unknown.key.is_none());
+    assert!(unknown.to_string().contains("min_dayz"), "{}", unknown);
+    assert!(RunConfig::from_toml_str("[sample]\nsize = \"ten\"").unwrap_err().to_string().contains("line 2"));
+}
+
+#[test]
+fn test_run() {
+    let input = temp_path("config_test_songs.csv");
+    let ranking = temp_path("config_test_ranking.csv");
+    let mut writer = csv::Writer::from_path(&input).unwrap();
+    for song in uniform_subset(dataset(), 60, SEED) {
+        writer.serialize(song).unwrap();
+    }
+    writer.flush().unwrap();
+
+    let config = RunConfig::from_toml_str(&format!(
+        "[input]\npath = {:?}\n[filters]\ntop = 40\n[weights]\npeak_position = 0.2\n[graph]\nkind = \"knn\"\nk = 5\n\
+         [analysis]\nmetrics = [\"harmonic\", \"closeness\"]\ntop = 3\ncompare = true\n[output]\nranking_csv = {:?}\n",
+        input.to_str().unwrap(),
+        ranking.to_str().unwrap()
+    ))
+    .unwrap();
+
+    let graph = config.build_graph().unwrap();
+    let mut empty = config.clone();
+    empty.filters.min_streams = Some(u64::MAX);
+    let error = empty.run(&mut Vec::new()).unwrap_err();
+    assert_eq!(error.downcast_ref::<ConfigError>().and_then(|e| e.key.as_deref()), Some("filters"));
+    let expected = Graph::build_knn_with_weights(config.songs().unwrap(), 5, &config.weights);
+    assert_eq!(graph.vertices.len(), 40);
+    assert_eq!(graph.adjacency_list, expected.adjacency_list);
+
+    let mut report = Vec::new();
+    config.run(&mut report).unwrap();
+    let report = String::from_utf8(report).unwrap();
+    assert!(report.contains("Most central by harmonic:") && report.contains("Most central by closeness:"));
+    assert_eq!(report.lines().filter(|line| line.starts_with("Depth ")).count(), 6);
+
+    let text = std::fs::read_to_string(&ranking).unwrap();
+    assert_eq!(text.lines().count(), 1 + 2 * 40);
+
+    // cancelling the second ranking keeps the first and the part of the second scored so far
+    let progress = CancelDuring { task: "closeness", after: 10, current: RefCell::default(), done: Cell::new(0) };
+    let mut report = Vec::new();
+    let error = config.run_observed(&mut report, &progress).unwrap_err();
+    assert!(error.to_string().contains("ranking"), "{}", error);
+    assert!(String::from_utf8(report).unwrap().contains("Cancelled: only 10 of 40 songs were scored"));
+    let text = std::fs::read_to_string(&ranking).unwrap();
+    assert_eq!(text.lines().count(), 1 + 40 + 10);
+    std::fs::remove_file(input).unwrap();
+    std::fs::remove_file(ranking).unwrap();
+}
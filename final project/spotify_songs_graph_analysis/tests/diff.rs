@@ -0,0 +1,67 @@
+mod common;
+
+use common::song;
+use spotify_songs_graph_analysis::centrality::Centrality;
+use spotify_songs_graph_analysis::Graph;
+
+fn key(artist: &str, name: &str) -> (String, String) {
+    (artist.to_string(), name.to_string())
+}
+
+#[test]
+fn test_diff_songs_and_edges() {
+    let old = Graph::build_from_songs(vec![
+        song("A", "one", 10, 1, 100_000_000),
+        song("B", "two", 20, 5, 200_000_000),
+        song("C", "three", 30, 9, 300_000_000),
+    ]);
+    // "two" moved a lot, "three" barely, "one" left the chart and "four" is new
+    let new = Graph::build_from_songs(vec![
+        song("B ", "two", 400, 50, 250_000_000),
+        song("C", "three", 31, 9, 300_000_000),
+        song("D", "four", 5, 2, 50_000_000),
+    ]);
+
+    let diff = old.diff(&new, 5, None);
+    assert_eq!(diff.added, vec![key("D", "four")]);
+    assert_eq!(diff.removed, vec![key("A", "one")]);
+    assert_eq!(diff.changed_edges.len(), 1);
+    let change = &diff.changed_edges[0];
+    assert_eq!((&change.from, &change.to), (&key("B", "two"), &key("C", "three")));
+    assert!(change.old_weight.unwrap() > change.new_weight.unwrap());
+    assert!(diff.rank_moves.is_empty());
+
+    // a graph compared with itself has no differences
+    let same = old.diff(&old, 0, Some(Centrality::Harmonic));
+    assert!(same.added.is_empty() && same.removed.is_empty() && same.changed_edges.is_empty());
+    assert!(same.rank_moves.iter().all(|rank_move| rank_move.movement == 0));
+}
+
+#[test]
+fn test_diff_missing_edges_and_rank_moves() {
+    let songs: Vec<_> = (0..4).map(|i| song("X", &format!("song {}", i), 1, 1, 1)).collect();
+    let mut old = Graph::new();
+    let mut new = Graph::new();
+    for song in &songs {
+        old.add_vertex(song.clone());
+        new.add_vertex(song.clone());
+    }
+    // a star around 0 becomes a star around 3
+    for v in 1..4 {
+        old.add_edge(0, v, 1);
+    }
+    for v in 0..3 {
+        new.add_edge(3, v, 1);
+    }
+
+    let diff = old.diff(&new, 0, Some(Centrality::Harmonic));
+    // 0-1 and 0-2 disappear, 1-3 and 2-3 appear, 0-3 stays
+    assert_eq!(diff.changed_edges.len(), 4);
+    assert!(diff.changed_edges.iter().any(|change| change.from.1 == "song 0" && change.to.1 == "song 1" && change.new_weight.is_none()));
+    assert!(diff.changed_edges.iter().any(|change| change.from.1 == "song 1" && change.to.1 == "song 3" && change.old_weight.is_none()));
+
+    // the old and new centres swap places, equal moves are ordered by name
+    let moves: Vec<_> = diff.rank_moves.iter().map(|m| (m.song.1.as_str(), m.old_rank, m.new_rank, m.movement)).collect();
+    assert_eq!(moves, vec![("song 0", 1, 2, -1), ("song 3", 2, 1, 1), ("song 1", 2, 2, 0), ("song 2", 2, 2, 0)]);
+    assert!(diff.to_string().contains("Largest harmonic rank moves"));
+}
@@ -0,0 +1,53 @@
+mod common;
+
+use common::{graph_with_vertices, path_fixture};
+
+#[test]
+fn test_strength() {
+    let mut graph = graph_with_vertices(4);
+    graph.add_edge(0, 1, 2);
+    graph.add_edge(1, 2, 4);
+    graph.add_edge(0, 2, 9);
+    assert_eq!(graph.strengths(), vec![11, 6, 13, 0]);
+
+    let distribution = graph.strength_distribution(2);
+    assert_eq!((distribution.min, distribution.max, distribution.mean), (0.0, 13.0, 7.5));
+    assert_eq!(distribution.histogram.iter().map(|bin| bin.count).collect::<Vec<_>>(), vec![2, 2]);
+    assert_eq!(graph.stats().strength.median, 8.5);
+}
+
+#[test]
+fn test_ego_network_hops() {
+    let graph = path_fixture();
+    assert_eq!(graph.ego_vertices(2, 0, None), vec![2]);
+    let one_hop = graph.ego_vertices(2, 1, None);
+    let two_hops = graph.ego_vertices(2, 2, None);
+    assert_eq!(one_hop[0], 2);
+    assert!(one_hop.len() < two_hops.len());
+    assert!(one_hop.iter().all(|v| two_hops.contains(v)));
+
+    // the ego network keeps every edge between its songs, not just the search tree
+    let ego = graph.ego_network(2, 2, None);
+    assert_eq!(ego.vertices.len(), two_hops.len());
+    assert_eq!(ego.vertices[0].song_name, graph.vertices[2].song_name);
+    for (i, &u) in two_hops.iter().enumerate() {
+        let inside = graph.adjacency_list[u].iter().filter(|(v, _)| two_hops.contains(v)).count();
+        assert_eq!(ego.adjacency_list[i].len(), inside);
+    }
+}
+
+#[test]
+fn test_ego_network_weight_cutoff() {
+    let mut graph = graph_with_vertices(5);
+    graph.add_edge(0, 1, 10);
+    graph.add_edge(0, 2, 1);
+    graph.add_edge(1, 2, 10);
+    graph.add_edge(2, 3, 10);
+    graph.add_edge(3, 4, 10);
+
+    // 2 is still reached through 1, but the light 0-2 edge is left out
+    let ego = graph.ego_network(0, 2, Some(5));
+    assert_eq!(graph.ego_vertices(0, 2, Some(5)), vec![0, 1, 2]);
+    assert_eq!(ego.adjacency_list, vec![vec![(1, 10)], vec![(0, 10), (2, 10)], vec![(1, 10)]]);
+    assert_eq!(graph.ego_vertices(0, 2, None), vec![0, 1, 2, 3]);
+}
@@ -0,0 +1,73 @@
+mod common;
+
+use common::{graph_with_vertices, temp_path};
+use spotify_songs_graph_analysis::embedding::DeepWalkOptions;
+use spotify_songs_graph_analysis::generators::{Generator, WeightSpec};
+use spotify_songs_graph_analysis::Graph;
+
+// two 8-cliques joined by one light edge between vertices 7 and 8
+fn two_cliques() -> Graph {
+    let mut graph = graph_with_vertices(16);
+    for block in [0, 8] {
+        for u in block..block + 8 {
+            for v in (u + 1)..block + 8 {
+                graph.add_edge(u, v, 10);
+            }
+        }
+    }
+    graph.add_edge(7, 8, 1);
+    graph
+}
+
+fn cosine(a: &[f64], b: &[f64]) -> f64 {
+    let dot: f64 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norm = |v: &[f64]| v.iter().map(|x| x * x).sum::<f64>().sqrt();
+    dot / (norm(a) * norm(b))
+}
+
+#[test]
+fn test_spectral_embedding_separates_clusters() {
+    let embedding = two_cliques().spectral_embedding(2, 200, 1);
+    assert_eq!(embedding.vectors.len(), 16);
+    assert_eq!(embedding.dimensions(), 2);
+
+    // the first coordinate is the Fiedler vector, whose sign splits the two cliques
+    let side = embedding.vectors[0][0].signum();
+    assert!((0..8).all(|v| embedding.vectors[v][0].signum() == side));
+    assert!((8..16).all(|v| embedding.vectors[v][0].signum() == -side));
+}
+
+#[test]
+fn test_spectral_embedding_orders_a_path() {
+    let graph = Generator::new(1, WeightSpec::Constant(1)).path(6);
+    let embedding = graph.spectral_embedding(1, 500, 1);
+    let fiedler: Vec<f64> = embedding.vectors.iter().map(|vector| vector[0]).collect();
+    let increasing = fiedler.windows(2).all(|pair| pair[0] < pair[1]);
+    let decreasing = fiedler.windows(2).all(|pair| pair[0] > pair[1]);
+    assert!(increasing || decreasing, "{:?}", fiedler);
+}
+
+#[test]
+fn test_deepwalk() {
+    let graph = two_cliques();
+    let options = DeepWalkOptions { dimensions: 8, walks_per_vertex: 20, walk_length: 20, epochs: 3, seed: 1, ..DeepWalkOptions::default() };
+    let embedding = graph.deepwalk(&options);
+    assert_eq!(embedding, graph.deepwalk(&options));
+    assert_eq!(embedding.dimensions(), 8);
+
+    let vectors = &embedding.vectors;
+    assert!(cosine(&vectors[1], &vectors[2]) > cosine(&vectors[1], &vectors[12]));
+    assert!(cosine(&vectors[13], &vectors[14]) > cosine(&vectors[13], &vectors[3]));
+}
+
+#[test]
+fn test_embedding_csv() {
+    let graph = two_cliques();
+    let path = temp_path("embedding_test.csv");
+    graph.spectral_embedding(3, 10, 1).write_csv(&graph, path.to_str().unwrap()).unwrap();
+
+    let text = std::fs::read_to_string(&path).unwrap();
+    assert_eq!(text.lines().next(), Some("artist_name,song_name,d0,d1,d2"));
+    assert_eq!(text.lines().count(), 17);
+    std::fs::remove_file(path).unwrap();
+}
@@ -0,0 +1,92 @@
+use spotify_songs_graph_analysis::generators::{Generator, WeightSpec};
+
+fn unit() -> Generator {
+    Generator::new(210, WeightSpec::Constant(1))
+}
+
+fn edge_count(graph: &spotify_songs_graph_analysis::Graph) -> usize {
+    graph.adjacency_list.iter().map(|adj_list| adj_list.len()).sum::<usize>() / 2
+}
+
+#[test]
+fn test_complete_graph() {
+    let graph = unit().complete(6);
+    assert_eq!(edge_count(&graph), 15);
+    assert_eq!(graph.triangle_count(), 20);
+    assert!((graph.average_clustering() - 1.0).abs() < 1e-9);
+    // every vertex reaches the other five at distance one
+    for (_, &(_, _, closeness)) in graph.closeness_centrality().iter() {
+        assert!((closeness - 6.0 / 5.0 * 1000.0).abs() < 1e-3);
+    }
+}
+
+#[test]
+fn test_star_and_path() {
+    let star = unit().star(5);
+    let scores = star.closeness_centrality();
+    assert!((scores[&0].2 - 5.0 / 4.0 * 1000.0).abs() < 1e-3);
+    assert!((scores[&1].2 - 5.0 / 7.0 * 1000.0).abs() < 1e-3);
+    assert_eq!(star.triangle_count(), 0);
+
+    let path = unit().path(6);
+    assert_eq!(edge_count(&path), 5);
+    assert_eq!(path.dijkstra(0)[5], 5.0);
+    assert!(path.is_connected());
+}
+
+#[test]
+fn test_grid() {
+    let grid = unit().grid(3, 4);
+    assert_eq!(grid.vertices.len(), 12);
+    assert_eq!(edge_count(&grid), 3 * 3 + 4 * 2);
+    assert_eq!(grid.adjacency_list[0].len(), 2);
+    assert_eq!(grid.adjacency_list[5].len(), 4);
+    // manhattan distance between opposite corners
+    assert_eq!(grid.dijkstra(0)[11], 5.0);
+    assert_eq!(grid.triangle_count(), 0);
+}
+
+#[test]
+fn test_erdos_renyi() {
+    assert_eq!(edge_count(&unit().erdos_renyi(50, 0.0)), 0);
+    assert_eq!(edge_count(&unit().erdos_renyi(50, 1.0)), 50 * 49 / 2);
+
+    // 19900 pairs at p = 0.1: mean 1990, standard deviation about 42
+    let edges = edge_count(&unit().erdos_renyi(200, 0.1));
+    assert!((1790..=2190).contains(&edges));
+}
+
+#[test]
+fn test_barabasi_albert() {
+    let graph = unit().barabasi_albert(300, 3);
+    assert_eq!(edge_count(&graph), 6 + (300 - 4) * 3);
+    assert!(graph.is_connected());
+    assert!(graph.adjacency_list.iter().all(|adj_list| adj_list.len() >= 3));
+    // early vertices collect far more links than the minimum
+    assert!(graph.adjacency_list[0].len() > 15);
+}
+
+#[test]
+fn test_watts_strogatz() {
+    // without rewiring the ring lattice clustering is 3(k - 2) / 4(k - 1)
+    let lattice = unit().watts_strogatz(40, 6, 0.0);
+    assert_eq!(edge_count(&lattice), 40 * 3);
+    assert!((lattice.average_clustering() - 3.0 * 4.0 / (4.0 * 5.0)).abs() < 1e-9);
+
+    let rewired = unit().watts_strogatz(40, 6, 0.3);
+    assert_eq!(edge_count(&rewired), 40 * 3);
+    assert!(rewired.average_clustering() < lattice.average_clustering());
+}
+
+#[test]
+fn test_weights_and_seeds() {
+    let graph = Generator::new(7, WeightSpec::Uniform(5, 9)).erdos_renyi(30, 0.5);
+    assert!(graph.adjacency_list.iter().flatten().all(|&(_, weight)| (5..=9).contains(&weight)));
+
+    let again = Generator::new(7, WeightSpec::Uniform(5, 9)).erdos_renyi(30, 0.5);
+    assert_eq!(graph.adjacency_list, again.adjacency_list);
+
+    // reversed bounds mean the same range
+    let reversed = Generator::new(7, WeightSpec::Uniform(9, 5)).erdos_renyi(30, 0.5);
+    assert_eq!(reversed.adjacency_list, graph.adjacency_list);
+}
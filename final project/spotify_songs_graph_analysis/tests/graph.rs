@@ -0,0 +1,88 @@
+mod common;
+
+use common::{dataset, detour_fixture, path_fixture, SEED};
+use spotify_songs_graph_analysis::sample::uniform_subset;
+use spotify_songs_graph_analysis::query::SongQuery;
+use spotify_songs_graph_analysis::Graph;
+
+#[test]
+fn test_graph_construction_with_random_subset() {
+    let song_subset = uniform_subset(dataset(), 1000, SEED);
+    let graph = Graph::build_from_songs(song_subset);
+    assert!(!graph.vertices.is_empty());
+    assert_eq!(graph.vertices.len(), 1000);
+    assert_eq!(graph.adjacency_list[0].len(), 999);
+}
+
+#[test]
+fn test_dijkstra_algorithm() {
+    let song_subset = uniform_subset(dataset(), 1000, SEED);
+    let graph = Graph::build_from_songs(song_subset);
+    let distances = graph.dijkstra(0);
+    assert!(distances[1] > 0.0);
+    assert_ne!(distances[1], f32::MAX);
+}
+
+#[test]
+fn test_closeness_centrality() {
+    let song_subset = uniform_subset(dataset(), 1000, SEED);
+    let graph = Graph::build_from_songs(song_subset);
+    let centrality_scores = graph.closeness_centrality();
+    assert!(centrality_scores.iter().any(|(_, &(_, _, closeness))| closeness > 0.0));
+}
+
+#[test]
+fn test_graph_connectivity() {
+    let song_subset = uniform_subset(dataset(), 1000, SEED);
+    let graph = Graph::build_from_songs(song_subset);
+    assert!(graph.is_connected());
+}
+
+#[test]
+fn test_dijkstra_on_fixtures() {
+    assert_eq!(path_fixture().dijkstra(0), vec![0.0, 1.0, 3.0, 6.0]);
+    assert_eq!(path_fixture().dijkstra(2), vec![3.0, 2.0, 0.0, 3.0]);
+    assert_eq!(detour_fixture().dijkstra(0), vec![0.0, 1.0, 2.0, f32::MAX]);
+    assert_eq!(detour_fixture().dijkstra(3), vec![f32::MAX, f32::MAX, f32::MAX, 0.0]);
+}
+
+#[test]
+fn test_closeness_on_fixtures() {
+    // reachable count (including the source) over the sum of distances, times 1000
+    let scores = path_fixture().closeness_centrality();
+    let expected = [4.0 / 10.0, 4.0 / 8.0, 4.0 / 8.0, 4.0 / 14.0];
+    for (vertex, expected) in expected.iter().enumerate() {
+        assert!((scores[&vertex].2 - expected * 1000.0).abs() < 1e-3);
+    }
+
+    let scores = detour_fixture().closeness_centrality();
+    assert!((scores[&0].2 - 1000.0).abs() < 1e-3);
+    assert!((scores[&1].2 - 1500.0).abs() < 1e-3);
+    assert!(!detour_fixture().is_connected());
+    assert!(Graph::new().is_connected());
+    assert_eq!(detour_fixture().connected_components(), vec![0, 0, 0, 1]);
+}
+
+#[test]
+fn test_build_from_csv_filters_while_streaming() {
+    let path = concat!(env!("CARGO_MANIFEST_DIR"), "/Spotify_final_dataset.csv");
+    let query = SongQuery::new().total_streams(400_000_000..);
+    let graph = Graph::build_from_csv(path, |s| query.matches(s)).unwrap();
+    let expected: Vec<&str> = dataset().iter().filter(|s| s.total_streams >= 400_000_000).map(|s| s.song_name.as_str()).collect();
+    assert!(!expected.is_empty());
+    assert_eq!(graph.vertices.iter().map(|s| s.song_name.as_str()).collect::<Vec<_>>(), expected);
+    assert_eq!(graph.adjacency_list[0].len(), expected.len() - 1);
+
+    let query = SongQuery::new().include_artists(["Drake"]);
+    let drake = Graph::build_from_csv(path, |s| query.matches(s)).unwrap();
+    assert!(!drake.vertices.is_empty());
+    assert!(drake.vertices.iter().all(|s| s.artist_name.trim() == "Drake"));
+}
+
+#[test]
+fn test_build_from_iter_matches_build_from_songs() {
+    let songs = uniform_subset(dataset(), 50, SEED);
+    let from_iter = Graph::build_from_iter(songs.clone().into_iter().filter(|s| s.days > 100));
+    let from_vec = Graph::build_from_songs(songs.into_iter().filter(|s| s.days > 100).collect());
+    assert_eq!(from_iter.adjacency_list, from_vec.adjacency_list);
+}
@@ -0,0 +1,61 @@
+mod common;
+
+use common::{dataset, song, SEED};
+use spotify_songs_graph_analysis::index::{features, features_of, SongIndex};
+use spotify_songs_graph_analysis::sample::uniform_subset;
+use spotify_songs_graph_analysis::weights::feature_weight;
+use spotify_songs_graph_analysis::Graph;
+
+fn brute_force(songs: &[spotify_songs_graph_analysis::Song], point: &[f64; 3], k: usize) -> Vec<(usize, f64)> {
+    let mut all: Vec<(usize, f64)> = songs
+        .iter()
+        .enumerate()
+        .map(|(i, song)| (i, features(song).iter().zip(point).map(|(a, b)| (a - b).abs()).sum()))
+        .collect();
+    all.sort_by(|a, b| a.1.total_cmp(&b.1).then_with(|| a.0.cmp(&b.0)));
+    all.truncate(k);
+    all
+}
+
+#[test]
+fn test_nearest_matches_brute_force() {
+    let songs = uniform_subset(dataset(), 1000, SEED);
+    let index = SongIndex::new(&songs);
+    assert_eq!(index.len(), 1000);
+
+    for query in songs.iter().step_by(97) {
+        let point = features(query);
+        assert_eq!(index.nearest(&point, 7), brute_force(&songs, &point, 7));
+    }
+    // a song that is not in the dataset
+    let point = features_of(120, 3, 250_000_000);
+    assert_eq!(index.nearest(&point, 5), brute_force(&songs, &point, 5));
+    assert_eq!(index.nearest(&point, 0), vec![]);
+    assert_eq!(index.nearest(&point, 5000).len(), 1000);
+}
+
+#[test]
+fn test_distance_matches_feature_weight() {
+    let a = song("A", "a", 10, 4, 20_000_000);
+    let b = song("B", "b", 250, 60, 310_000_000);
+    let index = SongIndex::new(std::slice::from_ref(&b));
+    let (_, distance) = index.nearest_to_song(&a, 1)[0];
+    assert_eq!((1000.0 / (1.0 + distance)) as isize, feature_weight(&a, &b));
+}
+
+#[test]
+fn test_build_knn() {
+    let songs = uniform_subset(dataset(), 300, SEED);
+    let graph = Graph::build_knn(songs.clone(), 5);
+    assert_eq!(graph.vertices.len(), 300);
+    assert!(graph.adjacency_list.iter().all(|adj_list| adj_list.len() >= 5));
+    let edges: usize = graph.adjacency_list.iter().map(Vec::len).sum::<usize>() / 2;
+    assert!(edges <= 300 * 5);
+
+    // every song keeps its heaviest edge from the complete graph
+    let complete = Graph::build_from_songs(songs);
+    for (u, adj_list) in complete.adjacency_list.iter().enumerate() {
+        let heaviest = adj_list.iter().map(|&(_, weight)| weight).max().unwrap();
+        assert!(graph.adjacency_list[u].iter().any(|&(_, weight)| weight == heaviest));
+    }
+}
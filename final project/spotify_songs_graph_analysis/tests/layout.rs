@@ -0,0 +1,70 @@
+mod common;
+
+use common::{graph_with_vertices, temp_path};
+use spotify_songs_graph_analysis::layout::{Colouring, LayoutOptions};
+use spotify_songs_graph_analysis::Graph;
+
+// two triangles joined by one light edge between vertices 2 and 3
+fn two_triangles() -> Graph {
+    let mut graph = graph_with_vertices(6);
+    for (u, v) in [(0, 1), (1, 2), (0, 2), (3, 4), (4, 5), (3, 5)] {
+        graph.add_edge(u, v, 100);
+    }
+    graph.add_edge(2, 3, 1);
+    graph
+}
+
+fn distance(a: (f64, f64), b: (f64, f64)) -> f64 {
+    ((a.0 - b.0).powi(2) + (a.1 - b.1).powi(2)).sqrt()
+}
+
+#[test]
+fn test_fruchterman_reingold() {
+    let graph = two_triangles();
+    let options = LayoutOptions::default();
+    let positions = graph.fruchterman_reingold(&options).unwrap();
+
+    assert_eq!(positions, graph.fruchterman_reingold(&options).unwrap());
+    assert!(positions.iter().all(|&(x, y)| (0.0..=options.width).contains(&x) && (0.0..=options.height).contains(&y)));
+    // songs in the same triangle sit closer than songs in different ones
+    assert!(distance(positions[0], positions[1]) < distance(positions[0], positions[4]));
+    assert!(distance(positions[3], positions[5]) < distance(positions[1], positions[5]));
+
+    // an empty frame is an error rather than a panic
+    for options in [LayoutOptions { width: 0.0, ..LayoutOptions::default() }, LayoutOptions { height: -5.0, ..LayoutOptions::default() }] {
+        assert!(graph.fruchterman_reingold(&options).is_err());
+    }
+    let path = temp_path("layout_test_empty_frame.svg");
+    let options = LayoutOptions { width: f64::NAN, ..LayoutOptions::default() };
+    assert!(graph.write_svg(path.to_str().unwrap(), &options, Colouring::Component).is_err());
+    assert!(!path.exists());
+}
+
+#[test]
+fn test_label_propagation() {
+    let labels = two_triangles().label_propagation(1);
+    assert_eq!(labels, vec![0, 0, 0, 1, 1, 1]);
+    // isolated vertices keep their own community
+    assert_eq!(graph_with_vertices(3).label_propagation(1), vec![0, 1, 2]);
+}
+
+#[test]
+fn test_write_svg() {
+    let mut graph = two_triangles();
+    graph.vertices[0].song_name = "Rock & Roll <Live>".to_string();
+    let path = temp_path("layout_test.svg");
+    let options = LayoutOptions { min_edge_weight: 50, ..LayoutOptions::default() };
+    graph.write_svg(path.to_str().unwrap(), &options, Colouring::Community).unwrap();
+
+    let svg = std::fs::read_to_string(&path).unwrap();
+    assert!(svg.starts_with("<svg"));
+    assert_eq!(svg.matches("<circle").count(), 6);
+    assert_eq!(svg.matches("<line").count(), 6);
+    assert!(svg.contains("Rock &amp; Roll &lt;Live&gt;"));
+
+    // with one edge per vertex only 0-1, 0-2, 3-4 and 3-5 are some vertex's heaviest
+    let options = LayoutOptions { edges_per_vertex: 1, ..LayoutOptions::default() };
+    graph.write_svg(path.to_str().unwrap(), &options, Colouring::Component).unwrap();
+    assert_eq!(std::fs::read_to_string(&path).unwrap().matches("<line").count(), 4);
+    std::fs::remove_file(path).unwrap();
+}
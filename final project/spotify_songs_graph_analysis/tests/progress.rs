@@ -0,0 +1,120 @@
+mod common;
+
+use common::{dataset, SEED};
+use spotify_songs_graph_analysis::centrality::Centrality;
+use spotify_songs_graph_analysis::progress::{eta, format_duration, CancelToken, Progress};
+use spotify_songs_graph_analysis::sample::uniform_subset;
+use spotify_songs_graph_analysis::source::{SongIter, SongSource};
+use spotify_songs_graph_analysis::weights::WeightParams;
+use spotify_songs_graph_analysis::{Graph, Song};
+use std::cell::RefCell;
+use std::error::Error;
+use std::time::Duration;
+
+// Records every call and cancels once `cancel_after` steps are done.
+#[derive(Default)]
+struct Recorder {
+    calls: RefCell<Vec<String>>,
+    cancel_after: Option<usize>,
+}
+
+impl Progress for Recorder {
+    fn start(&self, task: &str, total: usize) {
+        self.calls.borrow_mut().push(format!("start {} {}", task, total));
+    }
+
+    fn advance(&self, done: usize) {
+        self.calls.borrow_mut().push(format!("advance {}", done));
+    }
+
+    fn finish(&self) {
+        self.calls.borrow_mut().push("finish".to_string());
+    }
+
+    fn is_cancelled(&self) -> bool {
+        let done = self.calls.borrow().iter().filter(|call| call.starts_with("advance")).count();
+        self.cancel_after.is_some_and(|limit| done >= limit)
+    }
+}
+
+struct Songs(Vec<Song>);
+
+impl SongSource for Songs {
+    fn songs(&self) -> Result<SongIter, Box<dyn Error>> {
+        Ok(Box::new(self.0.clone().into_iter().map(Ok)))
+    }
+}
+
+#[test]
+fn test_centrality_reports_every_source() {
+    let graph = Graph::build_from_songs(uniform_subset(dataset(), 20, SEED));
+    let recorder = Recorder::default();
+    let scores = graph.centrality_observed(Centrality::Harmonic, &recorder);
+    assert_eq!(scores, graph.harmonic_centrality());
+
+    let calls = recorder.calls.into_inner();
+    assert_eq!(calls.len(), 22);
+    assert_eq!(calls[0], "start harmonic 20");
+    assert_eq!(calls[20], "advance 20");
+    assert_eq!(calls[21], "finish");
+}
+
+#[test]
+fn test_cancelled_centrality_keeps_partial_scores() {
+    let graph = Graph::build_from_songs(uniform_subset(dataset(), 30, SEED));
+    let recorder = Recorder { cancel_after: Some(12), ..Recorder::default() };
+    let partial = graph.centrality_observed(Centrality::Closeness, &recorder);
+    assert_eq!(partial.len(), 12);
+    assert_eq!(recorder.calls.borrow().last().map(String::as_str), Some("finish"));
+
+    // the vertices that were scored have their final scores
+    let full = graph.closeness_centrality();
+    assert!(partial.iter().all(|(vertex, score)| full[vertex] == *score));
+
+    let token = CancelToken::new();
+    token.clone().cancel();
+    assert!(graph.ranking_observed(Centrality::Closeness, &token).is_empty());
+}
+
+#[test]
+fn test_build_progress_and_eta() {
+    let songs = uniform_subset(dataset(), 10, SEED);
+    let recorder = Recorder::default();
+    let graph = Graph::build_from_source_observed(&Songs(songs.clone()), |_| true, &recorder).unwrap();
+    assert_eq!(graph.adjacency_list, Graph::build_from_songs(songs.clone()).adjacency_list);
+    let calls = recorder.calls.into_inner();
+    assert_eq!(calls[0], "start connecting songs 45");
+    assert_eq!(calls[calls.len() - 2], "advance 45");
+
+    // cancelling after the first row leaves only the edges of vertex 0
+    let recorder = Recorder { cancel_after: Some(1), ..Recorder::default() };
+    let partial = Graph::build_from_source_observed(&Songs(songs), |_| true, &recorder).unwrap();
+    assert_eq!(partial.vertices.len(), 10);
+    assert_eq!(partial.adjacency_list.iter().map(Vec::len).sum::<usize>(), 2 * 9);
+
+    assert_eq!(eta(Duration::from_secs(10), 0, 100), None);
+    assert_eq!(eta(Duration::from_secs(10), 25, 100), Some(Duration::from_secs(30)));
+    assert_eq!(format_duration(Duration::from_secs(42)), "42s");
+    assert_eq!(format_duration(Duration::from_secs(185)), "3m05s");
+    assert_eq!(format_duration(Duration::from_secs(3720)), "1h02m");
+}
+
+#[test]
+fn test_knn_and_stats_progress() {
+    let songs = uniform_subset(dataset(), 10, SEED);
+    let recorder = Recorder::default();
+    let knn = Graph::build_knn_with_weights_observed(songs.clone(), 2, &WeightParams::default(), &recorder);
+    assert_eq!(knn.adjacency_list, Graph::build_knn(songs.clone(), 2).adjacency_list);
+    assert_eq!(recorder.calls.borrow()[0], "start linking nearest songs 10");
+
+    let graph = Graph::build_from_songs(songs);
+    let recorder = Recorder::default();
+    let stats = graph.stats_observed(&recorder);
+    assert_eq!(stats.triangle_count, 120);
+    assert_eq!(recorder.calls.borrow()[0], "start counting triangles 10");
+    assert_eq!(recorder.calls.borrow().last().map(String::as_str), Some("finish"));
+
+    // cancelling after the first vertex misses the triangles found from the others
+    let recorder = Recorder { cancel_after: Some(1), ..Recorder::default() };
+    assert!(graph.stats_observed(&recorder).triangle_count < 120);
+}
@@ -0,0 +1,51 @@
+mod common;
+
+use common::{dataset, song};
+use spotify_songs_graph_analysis::query::SongQuery;
+use spotify_songs_graph_analysis::Graph;
+
+fn names(songs: &[spotify_songs_graph_analysis::Song]) -> Vec<&str> {
+    songs.iter().map(|s| s.song_name.as_str()).collect()
+}
+
+#[test]
+fn test_field_predicates() {
+    let songs = vec![
+        song("A ", "First Light", 100, 1, 900),
+        song("B", "Second", 300, 12, 500),
+        song("C", "third light", 50, 4, 700),
+        song("A", "Fourth", 20, 30, 100),
+    ];
+
+    assert_eq!(names(&SongQuery::new().apply(songs.clone())), vec!["First Light", "Second", "third light", "Fourth"]);
+    assert_eq!(names(&SongQuery::new().peak_position(..=10).apply(songs.clone())), vec!["First Light", "third light"]);
+    assert_eq!(names(&SongQuery::new().days(50..300).apply(songs.clone())), vec!["First Light", "third light"]);
+    assert_eq!(names(&SongQuery::new().total_streams(500..).apply(songs.clone())), vec!["First Light", "Second", "third light"]);
+    assert_eq!(names(&SongQuery::new().song_name_contains("LIGHT").apply(songs.clone())), vec!["First Light", "third light"]);
+    assert_eq!(names(&SongQuery::new().artist_name_contains("b").apply(songs.clone())), vec!["Second"]);
+    assert_eq!(names(&SongQuery::new().include_artists(["A"]).apply(songs.clone())), vec!["First Light", "Fourth"]);
+    assert_eq!(names(&SongQuery::new().exclude_artists(["A", "C"]).apply(songs.clone())), vec!["Second"]);
+}
+
+#[test]
+fn test_top_by_streams_applies_after_filters() {
+    let songs = vec![
+        song("A", "a", 1, 1, 900),
+        song("B", "b", 1, 20, 950),
+        song("C", "c", 1, 2, 300),
+        song("D", "d", 1, 3, 800),
+    ];
+
+    let query = SongQuery::new().peak_position(..=10).top_by_streams(2);
+    assert!(!query.is_streamable());
+    assert_eq!(names(&query.apply(songs)), vec!["a", "d"]);
+}
+
+#[test]
+fn test_query_selects_graph_vertices() {
+    let query = SongQuery::new().peak_position(..=1).top_by_streams(20);
+    let graph = Graph::build_from_songs(query.apply(dataset().to_vec()));
+    assert_eq!(graph.vertices.len(), 20);
+    assert!(graph.vertices.iter().all(|s| s.peak_position == 1));
+    assert!(graph.is_connected());
+}
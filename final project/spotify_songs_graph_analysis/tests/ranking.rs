@@ -0,0 +1,55 @@
+mod common;
+
+use common::{path_fixture, song};
+use spotify_songs_graph_analysis::centrality::Centrality;
+use spotify_songs_graph_analysis::ranking::{compare_scores, rank};
+use spotify_songs_graph_analysis::Graph;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+fn graph_of(songs: &[(&str, u64)]) -> Graph {
+    let mut graph = Graph::new();
+    for (name, streams) in songs {
+        graph.add_vertex(song("X", name, 1, 1, *streams));
+    }
+    graph
+}
+
+#[test]
+fn test_compare_scores_puts_nan_last() {
+    assert_eq!(compare_scores(2.0, 1.0), Ordering::Less);
+    assert_eq!(compare_scores(f32::NAN, 1.0), Ordering::Greater);
+    assert_eq!(compare_scores(f32::NEG_INFINITY, f32::NAN), Ordering::Less);
+    assert_eq!(compare_scores(f32::NAN, f32::NAN), Ordering::Equal);
+    assert_eq!(compare_scores(0.0, -0.0), Ordering::Equal);
+}
+
+#[test]
+fn test_ties_break_on_streams_then_name() {
+    let graph = graph_of(&[("b", 10), ("a", 10), ("c", 50), ("d", 1), ("e", 1)]);
+    let scores: HashMap<_, _> = [3.0, 3.0, 3.0, f32::NAN, 1.0]
+        .into_iter()
+        .enumerate()
+        .map(|(v, score)| (v, (graph.vertices[v].song_name.clone(), "X".to_string(), score)))
+        .collect();
+
+    let ranking = rank(&graph, &scores);
+    let order: Vec<&str> = ranking.iter().map(|entry| entry.song_name.as_str()).collect();
+    assert_eq!(order, ["c", "a", "b", "e", "d"]);
+    let dense: Vec<usize> = ranking.iter().map(|entry| entry.dense_rank).collect();
+    assert_eq!(dense, [1, 1, 1, 2, 3]);
+    let competition: Vec<usize> = ranking.iter().map(|entry| entry.competition_rank).collect();
+    assert_eq!(competition, [1, 1, 1, 4, 5]);
+}
+
+#[test]
+fn test_ranking_is_stable_across_runs() {
+    let graph = path_fixture();
+    let first = graph.ranking(Centrality::Harmonic);
+    for _ in 0..5 {
+        assert_eq!(graph.ranking(Centrality::Harmonic), first);
+    }
+    // the two middle vertices are the most central
+    assert_eq!(first[0].vertex, 1);
+    assert_eq!(first.len(), 4);
+}
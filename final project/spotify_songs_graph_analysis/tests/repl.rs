@@ -0,0 +1,44 @@
+mod common;
+
+use common::{detour_fixture, path_fixture};
+use spotify_songs_graph_analysis::repl::{Outcome, Repl, SongCompleter};
+
+fn printed(repl: &mut Repl, line: &str) -> String {
+    match repl.execute(line) {
+        Ok(Outcome::Print(text)) => text,
+        other => panic!("`{}` gave {:?}", line, other),
+    }
+}
+
+#[test]
+fn test_repl_commands() {
+    let mut repl = Repl::new(detour_fixture());
+    assert_eq!(printed(&mut repl, "find SONG 3"), "[3] Song: song 3, Artist: X");
+    assert_eq!(printed(&mut repl, "neighbours 0 1"), "[2] Song: song 2, Artist: X, Weight: 5");
+    assert_eq!(printed(&mut repl, "path 0 2").lines().count(), 4);
+    assert_eq!(printed(&mut repl, "path 0 3"), "No path from 0 to 3");
+    assert!(printed(&mut repl, "central 1 harmonic").starts_with("Depth 1: [1]"));
+    assert!(printed(&mut repl, "stats").contains("Components"));
+    assert_eq!(repl.execute("quit"), Ok(Outcome::Quit));
+}
+
+#[test]
+fn test_repl_errors() {
+    let mut repl = Repl::new(path_fixture());
+    assert!(repl.execute("neighbours 7").unwrap_err().contains("no song 7"));
+    assert!(repl.execute("path 1").unwrap_err().contains("wrong arguments"));
+    assert!(repl.execute("central 5 pagerank").is_err());
+    assert!(repl.execute("dance").unwrap_err().contains("unknown command"));
+}
+
+#[test]
+fn test_completion() {
+    let mut graph = path_fixture();
+    graph.vertices[2].song_name = "Blinding Lights".to_string();
+    let completer = SongCompleter::new(&graph);
+
+    assert_eq!(completer.candidates("ne"), (0, vec!["neighbours".to_string()]));
+    assert_eq!(completer.candidates("find bli"), (5, vec!["Blinding Lights".to_string()]));
+    assert_eq!(completer.candidates("find song").1.len(), 3);
+    assert!(completer.candidates("path 1").1.is_empty());
+}
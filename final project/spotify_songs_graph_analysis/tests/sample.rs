@@ -0,0 +1,31 @@
+mod common;
+
+use common::{dataset, SEED};
+use spotify_songs_graph_analysis::sample::{stratified_subset, uniform_subset};
+use spotify_songs_graph_analysis::Song;
+
+fn names(songs: &[Song]) -> Vec<String> {
+    songs.iter().map(|s| s.song_name.clone()).collect()
+}
+
+#[test]
+fn test_seeded_subsets_are_reproducible() {
+    let first = uniform_subset(dataset(), 50, SEED);
+    let second = uniform_subset(dataset(), 50, SEED);
+    let other = uniform_subset(dataset(), 50, SEED + 1);
+    assert_eq!(names(&first), names(&second));
+    assert_ne!(names(&first), names(&other));
+}
+
+#[test]
+fn test_stratified_subset_keeps_stream_spread() {
+    let stratified = stratified_subset(dataset(), 100, 4, SEED);
+    assert_eq!(stratified.len(), 100);
+    assert_eq!(names(&stratified), names(&stratified_subset(dataset(), 100, 4, SEED)));
+
+    let mut streams: Vec<u64> = dataset().iter().map(|s| s.total_streams).collect();
+    streams.sort_unstable();
+    let median = streams[streams.len() / 2];
+    let above = stratified.iter().filter(|s| s.total_streams >= median).count();
+    assert!((49..=51).contains(&above));
+}
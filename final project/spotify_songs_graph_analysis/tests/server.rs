@@ -0,0 +1,100 @@
+mod common;
+
+use common::{detour_fixture, path_fixture};
+use serde_json::Value;
+use spotify_songs_graph_analysis::server::GraphServer;
+use std::io::{Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::thread;
+
+fn get(server: &mut GraphServer, target: &str) -> (u16, Value) {
+    let response = server.handle_request("GET", target);
+    (response.status, serde_json::from_str(&response.body).unwrap())
+}
+
+#[test]
+fn test_shortest_path() {
+    assert_eq!(detour_fixture().shortest_path(0, 2), Some((2.0, vec![0, 1, 2])));
+    assert_eq!(detour_fixture().shortest_path(0, 3), None);
+    assert_eq!(path_fixture().shortest_path(3, 3), Some((0.0, vec![3])));
+}
+
+#[test]
+fn test_song_routes() {
+    let mut server = GraphServer::new(path_fixture());
+
+    let (status, songs) = get(&mut server, "/songs?name=SONG+2");
+    assert_eq!(status, 200);
+    assert_eq!(songs[0]["vertex"], 2);
+    assert_eq!(songs.as_array().unwrap().len(), 1);
+
+    let (status, song) = get(&mut server, "/songs/1");
+    assert_eq!(status, 200);
+    assert_eq!(song["song_name"], "song 1");
+
+    let (status, neighbours) = get(&mut server, "/neighbours/1?limit=1");
+    assert_eq!(status, 200);
+    assert_eq!(neighbours[0]["vertex"], 2);
+    assert_eq!(neighbours[0]["weight"], 2);
+}
+
+#[test]
+fn test_path_and_central_routes() {
+    let mut server = GraphServer::new(detour_fixture());
+
+    let (status, path) = get(&mut server, "/path?from=0&to=2");
+    assert_eq!(status, 200);
+    assert_eq!(path["distance"], 2.0);
+    assert_eq!(path["songs"].as_array().unwrap().len(), 3);
+    assert_eq!(get(&mut server, "/path?from=0&to=3").0, 404);
+
+    let (status, central) = get(&mut server, "/central?metric=harmonic&limit=2");
+    assert_eq!(status, 200);
+    assert_eq!(central[0]["vertex"], 1);
+    assert_eq!(central.as_array().unwrap().len(), 2);
+}
+
+#[test]
+fn test_bad_requests() {
+    let mut server = GraphServer::new(path_fixture());
+    assert_eq!(get(&mut server, "/songs/9").0, 404);
+    assert_eq!(get(&mut server, "/songs/x").0, 400);
+    assert_eq!(get(&mut server, "/path?from=0").0, 400);
+    assert_eq!(get(&mut server, "/central?metric=pagerank").0, 400);
+    assert_eq!(get(&mut server, "/unknown").0, 404);
+    assert_eq!(server.handle_request("POST", "/songs").status, 405);
+}
+
+// Starts `server` on a free port and returns a function sending one raw request to it and
+// returning the raw response.
+fn spawn(server: GraphServer) -> impl Fn(&str) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap();
+    let mut server = server;
+    thread::spawn(move || server.serve_listener(listener));
+    move |request| {
+        let mut stream = TcpStream::connect(address).unwrap();
+        stream.write_all(request.as_bytes()).unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        response
+    }
+}
+
+#[test]
+fn test_cross_origin_is_opt_in_and_lines_are_capped() {
+    let request = spawn(GraphServer::new(path_fixture()));
+    let response = request("GET /songs/1 HTTP/1.1\r\nHost: localhost\r\n\r\n");
+    assert!(response.starts_with("HTTP/1.1 200 OK"), "{}", response);
+    assert!(!response.contains("Access-Control-Allow-Origin"));
+
+    // both requests end where the server gives up, so it closes with nothing left unread
+    let long_line = format!("GET /songs?name={}", "a".repeat(8 * 1024 + 1 - 16));
+    assert!(request(&long_line).starts_with("HTTP/1.1 431"));
+    let many_headers = format!("GET /songs/1 HTTP/1.1\r\n{}", "X-Filler: 1\r\n".repeat(101));
+    assert!(request(&many_headers).starts_with("HTTP/1.1 431"));
+
+    let request = spawn(GraphServer::new(path_fixture()).with_allow_origin("http://localhost:3000"));
+    let response = request("GET /songs/1 HTTP/1.1\r\n\r\n");
+    assert!(response.contains("Access-Control-Allow-Origin: http://localhost:3000\r\n"), "{}", response);
+}
@@ -0,0 +1,115 @@
+mod common;
+
+use common::{dataset, song, SEED};
+use spotify_songs_graph_analysis::sample::uniform_subset;
+use spotify_songs_graph_analysis::source::{
+    format_for_path, source_for_path, write_columnar, Column, ColumnMapping, ColumnarSource, DelimitedSource, JsonSource, SongSource,
+};
+use spotify_songs_graph_analysis::{Graph, Song};
+use std::fs;
+use std::path::PathBuf;
+
+fn temp_file(name: &str, contents: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("songs-{}-{}", std::process::id(), name));
+    fs::write(&path, contents).unwrap();
+    path
+}
+
+fn summary(songs: &[Song]) -> Vec<(String, String, u32, u32, u64)> {
+    songs.iter().map(|s| (s.artist_name.clone(), s.song_name.clone(), s.days, s.peak_position, s.total_streams)).collect()
+}
+
+#[test]
+fn test_csv_source_reads_the_dataset() {
+    let path = concat!(env!("CARGO_MANIFEST_DIR"), "/Spotify_final_dataset.csv");
+    let songs = DelimitedSource::csv(path).load().unwrap();
+    assert_eq!(songs.len(), dataset().len());
+    assert_eq!(songs[0].artist_name, dataset()[0].artist_name.trim());
+    assert_eq!(songs[0].total_streams, dataset()[0].total_streams);
+}
+
+#[test]
+fn test_tsv_with_column_mapping() {
+    let path = temp_file("mapped.tsv", "Streams\tTrack\tWeeks\tArtist\tBest\tDays\n1,500\tOne\t3\tA \t2\t20\n900\tTwo\t1\tB\t7.0\t5\n");
+    let mut mapping = ColumnMapping::default();
+    mapping.set("artist_name", Column::Name("Artist".to_string())).unwrap();
+    mapping.set("song_name", Column::Name("Track".to_string())).unwrap();
+    mapping.set("days", Column::Index(5)).unwrap();
+    mapping.set("peak_position", Column::Name("Best".to_string())).unwrap();
+    mapping.set("total_streams", Column::Name("Streams".to_string())).unwrap();
+    assert!(mapping.set("plays", Column::Index(0)).is_err());
+
+    let songs = DelimitedSource::tsv(&path).with_mapping(mapping).load().unwrap();
+    assert_eq!(summary(&songs), summary(&[song("A", "One", 20, 2, 1500), song("B", "Two", 5, 7, 900)]));
+    fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn test_bad_rows_and_missing_columns_are_reported() {
+    let path = temp_file("bad.csv", "artist_name,song_name,days,peak_position,total_streams\nA,One,ten,1,5\n");
+    let error = DelimitedSource::csv(&path).load().unwrap_err();
+    assert!(error.to_string().contains("row 1: invalid `days` value `ten`"));
+
+    let mut mapping = ColumnMapping::default();
+    mapping.set("days", Column::Name("weeks".to_string())).unwrap();
+    let error = DelimitedSource::csv(&path).with_mapping(mapping).songs().err().unwrap();
+    assert!(error.to_string().contains("no column `weeks`"));
+    fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn test_json_array_and_lines() {
+    let array = temp_file(
+        "songs.json",
+        r#"[{"artist_name": "A", "song_name": "One", "days": 1, "peak_position": 2, "total_streams": 3},
+            {"artist_name": "B", "song_name": "Two", "days": 4, "peak_position": 5, "total_streams": 6}]"#,
+    );
+    let lines = temp_file(
+        "songs.jsonl",
+        "{\"artist_name\": \"A\", \"song_name\": \"One\", \"days\": 1, \"peak_position\": 2, \"total_streams\": 3}\n\
+         {\"artist_name\": \"B\", \"song_name\": \"Two\", \"days\": 4, \"peak_position\": 5, \"total_streams\": 6}\n",
+    );
+    let expected = summary(&[song("A", "One", 1, 2, 3), song("B", "Two", 4, 5, 6)]);
+    assert_eq!(summary(&JsonSource::array(&array).load().unwrap()), expected);
+    assert_eq!(summary(&JsonSource::lines(&lines).load().unwrap()), expected);
+    assert_eq!(summary(&source_for_path(&lines).load().unwrap()), expected);
+    fs::remove_file(array).unwrap();
+    fs::remove_file(lines).unwrap();
+}
+
+#[test]
+fn test_columnar_round_trip() {
+    let songs = uniform_subset(dataset(), 200, SEED);
+    let path = std::env::temp_dir().join(format!("songs-{}-round-trip.songcol", std::process::id()));
+    write_columnar(&path, &songs).unwrap();
+
+    assert_eq!(summary(&ColumnarSource::new(&path).load().unwrap()), summary(&songs));
+    let graph = Graph::build_from_source(source_for_path(&path).as_ref(), |s| s.peak_position <= 10).unwrap();
+    assert_eq!(graph.vertices.len(), songs.iter().filter(|s| s.peak_position <= 10).count());
+    fs::remove_file(&path).unwrap();
+
+    let not_columnar = temp_file("plain.songcol", "artist_name,song_name\n");
+    assert!(ColumnarSource::new(&not_columnar).songs().is_err());
+    fs::remove_file(not_columnar).unwrap();
+}
+
+#[test]
+fn test_columnar_rejects_string_lengths_past_the_end() {
+    let path = std::env::temp_dir().join(format!("songs-{}-corrupt.songcol", std::process::id()));
+    write_columnar(&path, &uniform_subset(dataset(), 1, SEED)).unwrap();
+    let mut bytes = fs::read(&path).unwrap();
+    bytes[16..20].copy_from_slice(&u32::MAX.to_le_bytes());
+    fs::write(&path, bytes).unwrap();
+
+    let error = ColumnarSource::new(&path).songs().err().unwrap();
+    assert!(error.to_string().contains("past the end"), "{}", error);
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_format_for_path_falls_back_to_csv() {
+    assert_eq!(format_for_path("songs.TSV"), "tsv");
+    assert_eq!(format_for_path("songs.ndjson"), "jsonl");
+    assert_eq!(format_for_path("songs.txt"), "csv");
+    assert_eq!(format_for_path("songs"), "csv");
+}
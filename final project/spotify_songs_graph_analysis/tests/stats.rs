@@ -0,0 +1,27 @@
+mod common;
+
+use common::graph_with_vertices;
+
+#[test]
+fn test_graph_stats() {
+    let mut graph = graph_with_vertices(5);
+    graph.add_edge(0, 1, 2);
+    graph.add_edge(1, 2, 4);
+    graph.add_edge(0, 2, 9);
+    graph.add_edge(2, 3, 1);
+
+    let stats = graph.stats();
+    assert_eq!(stats.vertex_count, 5);
+    assert_eq!(stats.edge_count, 4);
+    assert!((stats.density - 0.4).abs() < 1e-9);
+    assert_eq!(stats.degree.min, 0);
+    assert_eq!(stats.degree.max, 3);
+    assert_eq!(stats.degree.histogram.get(&2), Some(&2));
+    assert_eq!(stats.weight.max, 9.0);
+    assert_eq!(stats.component_count, 2);
+    assert!(stats.paths.exact);
+    assert_eq!(stats.paths.diameter, 7.0);
+    // local coefficients are 1, 1, 1/3, 0 and 0
+    assert!((stats.clustering_coefficient - (7.0 / 3.0) / 5.0).abs() < 1e-9);
+    assert!(stats.to_json().unwrap().contains("\"component_count\": 2"));
+}
@@ -0,0 +1,71 @@
+use spotify_songs_graph_analysis::temporal::{songs_by_period, CentralityTimeline, ChartEntry, Period};
+
+fn entry(date: &str, position: u32, artist_name: &str, song_name: &str, streams: u64) -> ChartEntry {
+    ChartEntry {
+        date: date.to_string(),
+        position,
+        artist_name: artist_name.to_string(),
+        song_name: song_name.to_string(),
+        streams,
+    }
+}
+
+#[test]
+fn test_period_labels() {
+    assert_eq!(Period::Day.label("2021-03-07").unwrap(), "2021-03-07");
+    assert_eq!(Period::Month.label("2021-03-07").unwrap(), "2021-03");
+    assert_eq!(Period::Year.label("2021-03-07").unwrap(), "2021");
+    // 2021-03-07 is a Sunday, 2021-03-08 the following Monday
+    assert_eq!(Period::Week.label("2021-03-07").unwrap(), "2021-03-01");
+    assert_eq!(Period::Week.label("2021-03-08").unwrap(), "2021-03-08");
+    assert_eq!(Period::Week.label("2021-01-01").unwrap(), "2020-12-28");
+    assert_eq!(Period::Week.label("2020-03-01").unwrap(), "2020-02-24");
+    assert!(Period::Day.label("2021-02-29").is_err());
+    assert!(Period::Day.label("03/07/2021").is_err());
+    assert_eq!("week".parse::<Period>(), Ok(Period::Week));
+}
+
+#[test]
+fn test_songs_are_aggregated_per_period() {
+    let entries = vec![
+        entry("2021-01-01", 3, "A ", "One", 100),
+        entry("2021-01-02", 1, "A", "One", 150),
+        entry("2021-01-02", 2, "B", "Two", 90),
+        entry("2021-02-01", 5, "A", "One", 40),
+    ];
+
+    let periods = songs_by_period(&entries, Period::Month).unwrap();
+    assert_eq!(periods.iter().map(|p| p.label.as_str()).collect::<Vec<_>>(), vec!["2021-01", "2021-02"]);
+    let one = &periods[0].songs[0];
+    assert_eq!((one.artist_name.as_str(), one.days, one.peak_position, one.total_streams), ("A", 2, 1, 250));
+    assert_eq!(periods[0].songs.len(), 2);
+    assert_eq!(periods[1].songs.len(), 1);
+}
+
+#[test]
+fn test_closeness_timeline_tracks_movers() {
+    // C starts as an outlier, then matches the other songs exactly in the second month
+    let mut entries = vec![];
+    for (month, c_streams) in [("2021-01-15", 90_000_000), ("2021-02-15", 1_000_000)] {
+        entries.push(entry(month, 1, "A", "a", 1_000_000));
+        entries.push(entry(month, 2, "B", "b", 2_000_000));
+        entries.push(entry(month, 3, "C", "c", c_streams));
+    }
+    entries.push(entry("2021-02-15", 4, "D", "d", 1_500_000));
+
+    let timeline = CentralityTimeline::build(&entries, Period::Month).unwrap();
+    assert_eq!(timeline.periods, vec!["2021-01", "2021-02"]);
+    assert_eq!(timeline.songs.len(), 4);
+
+    let d = timeline.songs.iter().find(|s| s.song_name == "d").unwrap();
+    assert!(d.scores[0].is_none() && d.scores[1].is_some());
+    assert_eq!(d.net_change(), 0.0);
+
+    let c = timeline.songs.iter().find(|s| s.song_name == "c").unwrap();
+    // closeness reads the similarity weights as lengths, so becoming similar moves C away from
+    // the others: its distance sum grows from 1020 over 3 songs to 2799 over 4
+    assert!((c.net_change() - (4000.0 / 2799.0 - 3000.0 / 1020.0)).abs() < 1e-4, "{}", c.net_change());
+    assert_eq!(timeline.falling(1)[0].song_name, "c");
+    let movers: Vec<&str> = timeline.rising(10).into_iter().chain(timeline.falling(10)).map(|s| s.song_name.as_str()).collect();
+    assert!(!movers.contains(&"d"));
+}
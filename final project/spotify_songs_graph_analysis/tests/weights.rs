@@ -0,0 +1,43 @@
+mod common;
+
+use common::{dataset, song, SEED};
+use spotify_songs_graph_analysis::sample::uniform_subset;
+use spotify_songs_graph_analysis::weights::{connectivity_threshold, feature_weight, weight_distribution};
+use spotify_songs_graph_analysis::Graph;
+
+#[test]
+fn test_feature_weight_matches_graph_edges() {
+    let songs = vec![song("A", "a", 10, 1, 5_000_000), song("B", "b", 30, 8, 9_000_000), song("C", "c", 10, 1, 5_000_000)];
+    let graph = Graph::build_from_songs(songs.clone());
+    assert_eq!(graph.adjacency_list[0], vec![(1, feature_weight(&songs[0], &songs[1])), (2, 1000)]);
+}
+
+#[test]
+fn test_weight_distribution() {
+    let songs = uniform_subset(dataset(), 200, SEED);
+    let exact = weight_distribution(&songs, usize::MAX, 10, SEED);
+    assert!(exact.exact);
+    assert_eq!(exact.sampled_pairs, 200 * 199 / 2);
+    assert_eq!(exact.histogram.iter().map(|bin| bin.count).sum::<usize>(), exact.sampled_pairs);
+    assert!(exact.quantiles.windows(2).all(|pair| pair[0].1 <= pair[1].1));
+
+    let sampled = weight_distribution(&songs, 5000, 10, SEED);
+    assert!(!sampled.exact);
+    assert_eq!(sampled.sampled_pairs, 5000);
+    assert_eq!(sampled.to_json().unwrap(), weight_distribution(&songs, 5000, 10, SEED).to_json().unwrap());
+}
+
+#[test]
+fn test_suggested_thresholds() {
+    let songs = uniform_subset(dataset(), 200, SEED);
+    let distribution = weight_distribution(&songs, usize::MAX, 10, SEED);
+
+    let graph = Graph::build_from_songs_with_threshold(songs.clone(), distribution.threshold_for_average_degree(10.0));
+    let mean_degree = graph.stats().degree.mean;
+    assert!((10.0..12.0).contains(&mean_degree), "mean degree {}", mean_degree);
+
+    // the connectivity threshold is the highest one that keeps every song reachable
+    let threshold = connectivity_threshold(&songs).unwrap();
+    assert!(Graph::build_from_songs_with_threshold(songs.clone(), threshold).is_connected());
+    assert!(!Graph::build_from_songs_with_threshold(songs, threshold + 1).is_connected());
+}